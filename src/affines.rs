@@ -0,0 +1,255 @@
+use core::ops::Mul;
+use crate::vectors::VecN;
+use crate::points::Point;
+use crate::matrixs::Mat;
+use crate::quaternions::Quat;
+use crate::errors::MatrixError;
+
+// Linear map followed by a translation, cheaper to store and invert than a full Mat<3,3>
+#[derive(Clone, Debug, PartialEq)]
+pub struct Affine2 {
+    pub linear: Mat<2,2>,
+    pub translation: VecN<2>,
+}
+
+impl Affine2 {
+    pub fn new(linear: Mat<2,2>, translation: VecN<2>) -> Affine2 {
+        Affine2 {linear, translation}
+    }
+
+    pub fn identity() -> Affine2 {
+        Affine2::new(Mat::identity(), VecN::new())
+    }
+
+    pub fn from_translation(translation: VecN<2>) -> Affine2 {
+        Affine2::new(Mat::identity(), translation)
+    }
+
+    pub fn from_linear(linear: Mat<2,2>) -> Affine2 {
+        Affine2::new(linear, VecN::new())
+    }
+
+    pub fn transform_point(&self, point: &Point<2>) -> Point<2> {
+        Point::from(&self.linear * point.vals + self.translation)
+    }
+
+    pub fn transform_vector(&self, vector: &VecN<2>) -> VecN<2> {
+        &self.linear * vector
+    }
+
+    // Only the linear part needs a real inverse
+    pub fn inverse(&self) -> Result<Affine2, MatrixError> {
+        let linear = self.linear.inverse()?;
+        let translation = &linear * self.translation * -1.0;
+        Ok(Affine2::new(linear, translation))
+    }
+}
+
+// a * b applies b first, the same as multiplying the matrices
+impl Mul<&Affine2> for &Affine2 {
+    type Output = Affine2;
+
+    fn mul(self, other: &Affine2) -> Affine2 {
+        Affine2::new(&self.linear * &other.linear, self.transform_vector(&other.translation) + self.translation)
+    }
+}
+impl Mul<Affine2> for &Affine2 {
+    type Output = Affine2;
+    fn mul(self, other: Affine2) -> Affine2 {
+        self * &other
+    }
+}
+impl Mul<&Affine2> for Affine2 {
+    type Output = Affine2;
+    fn mul(self, other: &Affine2) -> Affine2 {
+        &self * other
+    }
+}
+impl Mul<Affine2> for Affine2 {
+    type Output = Affine2;
+    fn mul(self, other: Affine2) -> Affine2 {
+        &self * &other
+    }
+}
+
+impl From<&Affine2> for Mat<3,3> {
+    fn from(affine: &Affine2) -> Mat<3,3> {
+        let l = &affine.linear;
+        let t = &affine.translation;
+        Mat::from([[l[0][0], l[0][1], t[0]],
+                    [l[1][0], l[1][1], t[1]],
+                    [0.0, 0.0, 1.0]])
+    }
+}
+
+// The bottom row is assumed to be [0, 0, 1]
+impl From<&Mat<3,3>> for Affine2 {
+    fn from(mat: &Mat<3,3>) -> Affine2 {
+        Affine2::new(Mat::from([[mat[0][0], mat[0][1]],
+                                [mat[1][0], mat[1][1]]]),
+                     VecN::from([mat[0][2], mat[1][2]]))
+    }
+}
+
+// Linear map followed by a translation, cheaper to store and invert than a full Mat<4,4>
+#[derive(Clone, Debug, PartialEq)]
+pub struct Affine3 {
+    pub linear: Mat<3,3>,
+    pub translation: VecN<3>,
+}
+
+impl Affine3 {
+    pub fn new(linear: Mat<3,3>, translation: VecN<3>) -> Affine3 {
+        Affine3 {linear, translation}
+    }
+
+    pub fn identity() -> Affine3 {
+        Affine3::new(Mat::identity(), VecN::new())
+    }
+
+    pub fn from_translation(translation: VecN<3>) -> Affine3 {
+        Affine3::new(Mat::identity(), translation)
+    }
+
+    pub fn from_linear(linear: Mat<3,3>) -> Affine3 {
+        Affine3::new(linear, VecN::new())
+    }
+
+    pub fn transform_point(&self, point: &Point<3>) -> Point<3> {
+        Point::from(&self.linear * point.vals + self.translation)
+    }
+
+    pub fn transform_vector(&self, vector: &VecN<3>) -> VecN<3> {
+        &self.linear * vector
+    }
+
+    // Only the linear part needs a real inverse
+    pub fn inverse(&self) -> Result<Affine3, MatrixError> {
+        let linear = self.linear.inverse()?;
+        let translation = &linear * self.translation * -1.0;
+        Ok(Affine3::new(linear, translation))
+    }
+}
+
+// a * b applies b first, the same as multiplying the matrices
+impl Mul<&Affine3> for &Affine3 {
+    type Output = Affine3;
+
+    fn mul(self, other: &Affine3) -> Affine3 {
+        Affine3::new(&self.linear * &other.linear, self.transform_vector(&other.translation) + self.translation)
+    }
+}
+impl Mul<Affine3> for &Affine3 {
+    type Output = Affine3;
+    fn mul(self, other: Affine3) -> Affine3 {
+        self * &other
+    }
+}
+impl Mul<&Affine3> for Affine3 {
+    type Output = Affine3;
+    fn mul(self, other: &Affine3) -> Affine3 {
+        &self * other
+    }
+}
+impl Mul<Affine3> for Affine3 {
+    type Output = Affine3;
+    fn mul(self, other: Affine3) -> Affine3 {
+        &self * &other
+    }
+}
+
+impl From<&Affine3> for Mat<4,4> {
+    fn from(affine: &Affine3) -> Mat<4,4> {
+        let l = &affine.linear;
+        let t = &affine.translation;
+        Mat::from([[l[0][0], l[0][1], l[0][2], t[0]],
+                    [l[1][0], l[1][1], l[1][2], t[1]],
+                    [l[2][0], l[2][1], l[2][2], t[2]],
+                    [0.0, 0.0, 0.0, 1.0]])
+    }
+}
+
+// The bottom row is assumed to be [0, 0, 0, 1]
+impl From<&Mat<4,4>> for Affine3 {
+    fn from(mat: &Mat<4,4>) -> Affine3 {
+        Affine3::new(mat.linear_part(), VecN::from([mat[0][3], mat[1][3], mat[2][3]]))
+    }
+}
+
+// Rotation followed by a translation, the inverse is just a conjugate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Isometry3 {
+    pub rotation: Quat,
+    pub translation: VecN<3>,
+}
+
+impl Isometry3 {
+    pub fn new(rotation: Quat, translation: VecN<3>) -> Isometry3 {
+        Isometry3 {rotation: rotation.normalize(), translation}
+    }
+
+    pub fn identity() -> Isometry3 {
+        Isometry3::new(Quat::identity(), VecN::new())
+    }
+
+    pub fn from_translation(translation: VecN<3>) -> Isometry3 {
+        Isometry3::new(Quat::identity(), translation)
+    }
+
+    pub fn from_rotation(rotation: Quat) -> Isometry3 {
+        Isometry3::new(rotation, VecN::new())
+    }
+
+    pub fn transform_point(&self, point: &Point<3>) -> Point<3> {
+        Point::from(self.rotation.rotate(&point.vals) + self.translation)
+    }
+
+    pub fn transform_vector(&self, vector: &VecN<3>) -> VecN<3> {
+        self.rotation.rotate(vector)
+    }
+
+    pub fn inverse(&self) -> Isometry3 {
+        let rotation = self.rotation.conjugate();
+        let translation = rotation.rotate(&self.translation) * -1.0;
+        Isometry3 {rotation, translation}
+    }
+}
+
+// a * b applies b first, the same as multiplying the matrices
+impl Mul<&Isometry3> for &Isometry3 {
+    type Output = Isometry3;
+
+    fn mul(self, other: &Isometry3) -> Isometry3 {
+        Isometry3::new(self.rotation * other.rotation, self.transform_vector(&other.translation) + self.translation)
+    }
+}
+impl Mul<Isometry3> for &Isometry3 {
+    type Output = Isometry3;
+    fn mul(self, other: Isometry3) -> Isometry3 {
+        *self * other
+    }
+}
+impl Mul<&Isometry3> for Isometry3 {
+    type Output = Isometry3;
+    fn mul(self, other: &Isometry3) -> Isometry3 {
+        self * *other
+    }
+}
+impl Mul<Isometry3> for Isometry3 {
+    type Output = Isometry3;
+    fn mul(self, other: Isometry3) -> Isometry3 {
+        &self * &other
+    }
+}
+
+impl From<&Isometry3> for Affine3 {
+    fn from(iso: &Isometry3) -> Affine3 {
+        Affine3::new(Mat::from(&iso.rotation), iso.translation)
+    }
+}
+
+impl From<&Isometry3> for Mat<4,4> {
+    fn from(iso: &Isometry3) -> Mat<4,4> {
+        Mat::from(&Affine3::from(iso))
+    }
+}
@@ -0,0 +1,428 @@
+use alloc::alloc::{alloc_zeroed, handle_alloc_error, Layout};
+use alloc::boxed::Box;
+use alloc::{vec, vec::Vec};
+use core::ops::{Add,Sub,Mul,Div,Deref,DerefMut};
+use core::ptr::NonNull;
+use crate::matrixs::{Mat, gemm, invert_in_place, characteristic_polynomial_in_place, exp_in_place, sqrt_in_place, ln_in_place};
+use crate::vectors::VecN;
+use crate::errors::MatrixError;
+
+// A Mat that lives on the heap, for sizes like Mat<512, 512> (1MB) that overflow the stack
+// once a few temporaries are involved. Everything on Mat that takes &self works through
+// Deref. Every operation that builds a new matrix (exp, sqrt, adjugate, ...) is redone
+// here so the result and its temporaries are all in heap memory.
+#[derive(Debug)]
+pub struct BoxedMat<const N: usize, const M: usize> {
+    mat: Box<Mat<N, M>>,
+}
+
+// Box::new(Mat::new()) would build the whole matrix on the stack before moving it.
+// Debug builds reserve stack for every local up front, so no Mat value can appear here at all.
+fn zeroed<const N: usize, const M: usize>() -> Box<Mat<N, M>> {
+    let layout = Layout::new::<Mat<N, M>>();
+    unsafe {
+        let ptr = if layout.size() == 0 {
+            NonNull::<Mat<N, M>>::dangling().as_ptr()
+        } else {
+            alloc_zeroed(layout) as *mut Mat<N, M>
+        };
+        if ptr.is_null() {
+            handle_alloc_error(layout);
+        }
+        // Mat is nothing but f32s so all zero bytes is a matrix full of 0.0
+        Box::from_raw(ptr)
+    }
+}
+
+impl<const N: usize, const M: usize> BoxedMat<N, M> {
+    pub fn new() -> BoxedMat<N, M> {
+        BoxedMat {mat: zeroed()}
+    }
+
+    // f(i, j) gives the value at row i and column j
+    pub fn from_fn<F: FnMut(usize, usize) -> f32>(mut f: F) -> BoxedMat<N, M> {
+        let mut new_mat = BoxedMat::new();
+        for i in 0..N {
+            for j in 0..M {
+                new_mat[i][j] = f(i, j);
+            }
+        }
+        new_mat
+    }
+
+    pub fn transpose(&self) -> BoxedMat<M, N> {
+        BoxedMat::from_fn(|i, j| self[j][i])
+    }
+
+    pub fn into_inner(self) -> Box<Mat<N, M>> {
+        self.mat
+    }
+}
+
+impl<const N: usize> BoxedMat<N, N> {
+    pub fn identity() -> BoxedMat<N, N> {
+        let mut mat = BoxedMat::new();
+        for i in 0..N {
+            mat[i][i] = 1.0;
+        }
+        mat
+    }
+
+    pub fn inverse(&self) -> Result<BoxedMat<N, N>, MatrixError> {
+        let mut mat = self.clone();
+        let mut inv = BoxedMat::identity();
+        invert_in_place(&mut mat, &mut inv)?;
+        Ok(inv)
+    }
+
+    // Same as Mat::pow, swapping between two heap buffers instead of making temporaries
+    pub fn pow(&self, exp: i32) -> Result<BoxedMat<N, N>, MatrixError> {
+        let mut base = if exp < 0 {
+            self.inverse()?
+        } else {
+            self.clone()
+        };
+        let mut exp = exp.unsigned_abs();
+        let mut answer = BoxedMat::identity();
+        let mut scratch = BoxedMat::new();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                answer.mul_into(&*base, &mut *scratch);
+                core::mem::swap(&mut answer, &mut scratch);
+            }
+            base.mul_into(&*base, &mut *scratch);
+            core::mem::swap(&mut base, &mut scratch);
+            exp >>= 1;
+        }
+        Ok(answer)
+    }
+
+    // By elimination, the cofactor expansion Mat::det uses takes factorial time
+    pub fn det(&self) -> f32 {
+        eliminate_det(self.as_slice().to_vec(), N)
+    }
+
+    pub fn minor(&self, i: usize, j: usize) -> f32 {
+        assert!(i < N && j < N, "minor index ({}, {}) out of bounds for {}x{} matrix", i, j, N, N);
+        if N == 1 {
+            return 1.0;
+        }
+        let mut vals = Vec::with_capacity((N - 1) * (N - 1));
+        for row in (0..N).filter(|&row| row != i) {
+            vals.extend((0..N).filter(|&col| col != j).map(|col| self[row][col]));
+        }
+        eliminate_det(vals, N - 1)
+    }
+
+    pub fn cofactor(&self, i: usize, j: usize) -> f32 {
+        if (i + j) % 2 == 1 {
+            -self.minor(i, j)
+        } else {
+            self.minor(i, j)
+        }
+    }
+
+    pub fn cofactor_matrix(&self) -> BoxedMat<N, N> {
+        self.adjugate().transpose()
+    }
+
+    // det * inverse when there is an inverse. Singular matrices fall back to one minor
+    // per value, which is only practical for smaller sizes.
+    pub fn adjugate(&self) -> BoxedMat<N, N> {
+        match self.inverse() {
+            Ok(inv) => &inv * self.det(),
+            Err(_) => BoxedMat::from_fn(|i, j| self.cofactor(j, i)),
+        }
+    }
+
+    // The Mat versions of these run on the same helpers with heap buffers swapped in
+    pub fn characteristic_polynomial(&self) -> Vec<f32> {
+        let mut coeffs = vec![0.0; N + 1];
+        characteristic_polynomial_in_place(self, &mut coeffs, [&mut BoxedMat::new(), &mut BoxedMat::new()]);
+        coeffs
+    }
+
+    pub fn exp(&self) -> Result<BoxedMat<N, N>, MatrixError> {
+        let mut answer = BoxedMat::new();
+        exp_in_place(self, &mut answer, [&mut BoxedMat::new(), &mut BoxedMat::new(), &mut BoxedMat::new(), &mut BoxedMat::new()])?;
+        Ok(answer)
+    }
+
+    pub fn sqrt(&self) -> Result<BoxedMat<N, N>, MatrixError> {
+        let mut answer = BoxedMat::new();
+        sqrt_in_place(self, &mut answer, [&mut BoxedMat::new(), &mut BoxedMat::new(), &mut BoxedMat::new(), &mut BoxedMat::new()])?;
+        Ok(answer)
+    }
+
+    pub fn ln(&self) -> Result<BoxedMat<N, N>, MatrixError> {
+        let mut answer = BoxedMat::new();
+        ln_in_place(self, &mut answer, [&mut BoxedMat::new(), &mut BoxedMat::new(), &mut BoxedMat::new(), &mut BoxedMat::new(), &mut BoxedMat::new()])?;
+        Ok(answer)
+    }
+}
+
+// Gaussian elimination with partial pivoting on n x n row major values
+fn eliminate_det(mut vals: Vec<f32>, n: usize) -> f32 {
+    let mut det = 1.0;
+    for col in 0..n {
+        let mut pivot = col;
+        for row in col + 1..n {
+            if vals[row * n + col].abs() > vals[pivot * n + col].abs() {
+                pivot = row;
+            }
+        }
+        if vals[pivot * n + col] == 0.0 {
+            return 0.0;
+        }
+        if pivot != col {
+            for k in 0..n {
+                vals.swap(pivot * n + k, col * n + k);
+            }
+            det = -det;
+        }
+
+        let diagonal = vals[col * n + col];
+        det *= diagonal;
+        for row in col + 1..n {
+            let factor = vals[row * n + col] / diagonal;
+            for k in col..n {
+                vals[row * n + k] -= factor * vals[col * n + k];
+            }
+        }
+    }
+    det
+}
+
+impl<const N: usize, const M: usize> Default for BoxedMat<N, M> {
+    fn default() -> BoxedMat<N, M> {
+        BoxedMat::new()
+    }
+}
+
+impl<const N: usize, const M: usize> Clone for BoxedMat<N, M> {
+    fn clone(&self) -> BoxedMat<N, M> {
+        let mut new_mat = BoxedMat::new();
+        for i in 0..N {
+            new_mat[i] = self[i];
+        }
+        new_mat
+    }
+}
+
+impl<const N: usize, const M: usize> Deref for BoxedMat<N, M> {
+    type Target = Mat<N, M>;
+
+    fn deref(&self) -> &Mat<N, M> {
+        &self.mat
+    }
+}
+
+impl<const N: usize, const M: usize> DerefMut for BoxedMat<N, M> {
+    fn deref_mut(&mut self) -> &mut Mat<N, M> {
+        &mut self.mat
+    }
+}
+
+impl<const N: usize, const M: usize> From<Mat<N, M>> for BoxedMat<N, M> {
+    fn from(mat: Mat<N, M>) -> BoxedMat<N, M> {
+        BoxedMat {mat: Box::new(mat)}
+    }
+}
+
+impl<const N: usize, const M: usize> From<Box<Mat<N, M>>> for BoxedMat<N, M> {
+    fn from(mat: Box<Mat<N, M>>) -> BoxedMat<N, M> {
+        BoxedMat {mat}
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq for BoxedMat<N, M> {
+    fn eq(&self, other: &BoxedMat<N, M>) -> bool {
+        *self.mat == *other.mat
+    }
+}
+
+
+
+impl<const N: usize, const M: usize> Add<&BoxedMat<N, M>> for &BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn add(self, other: &BoxedMat<N, M>) -> BoxedMat<N, M> {
+        let mut new_mat = BoxedMat::new();
+        for i in 0..N {
+            new_mat[i] = self[i] + other[i];
+        }
+        new_mat
+    }
+}
+
+impl<const N: usize, const M: usize> Add<BoxedMat<N, M>> for &BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn add(self, other: BoxedMat<N, M>) -> BoxedMat<N, M> {
+        self + &other
+    }
+}
+
+impl<const N: usize, const M: usize> Add<&BoxedMat<N, M>> for BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn add(self, other: &BoxedMat<N, M>) -> BoxedMat<N, M> {
+        &self + other
+    }
+}
+
+impl<const N: usize, const M: usize> Add<BoxedMat<N, M>> for BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn add(self, other: BoxedMat<N, M>) -> BoxedMat<N, M> {
+        &self + &other
+    }
+}
+
+
+impl<const N: usize, const M: usize> Sub<&BoxedMat<N, M>> for &BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn sub(self, other: &BoxedMat<N, M>) -> BoxedMat<N, M> {
+        let mut new_mat = BoxedMat::new();
+        for i in 0..N {
+            new_mat[i] = self[i] - other[i];
+        }
+        new_mat
+    }
+}
+
+impl<const N: usize, const M: usize> Sub<BoxedMat<N, M>> for &BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn sub(self, other: BoxedMat<N, M>) -> BoxedMat<N, M> {
+        self - &other
+    }
+}
+
+impl<const N: usize, const M: usize> Sub<&BoxedMat<N, M>> for BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn sub(self, other: &BoxedMat<N, M>) -> BoxedMat<N, M> {
+        &self - other
+    }
+}
+
+impl<const N: usize, const M: usize> Sub<BoxedMat<N, M>> for BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn sub(self, other: BoxedMat<N, M>) -> BoxedMat<N, M> {
+        &self - &other
+    }
+}
+
+
+
+impl<const N: usize, const M: usize> Mul<f32> for &BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn mul(self, scalar: f32) -> BoxedMat<N, M> {
+        let mut new_mat = BoxedMat::new();
+        for i in 0..N {
+            new_mat[i] = self[i] * scalar;
+        }
+        new_mat
+    }
+}
+
+impl<const N: usize, const M: usize> Mul<f32> for BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn mul(self, scalar: f32) -> BoxedMat<N, M> {
+        &self * scalar
+    }
+}
+
+impl<const N: usize, const M: usize> Div<f32> for &BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn div(self, scalar: f32) -> BoxedMat<N, M> {
+        let mut new_mat = BoxedMat::new();
+        for i in 0..N {
+            new_mat[i] = self[i] / scalar;
+        }
+        new_mat
+    }
+}
+
+impl<const N: usize, const M: usize> Div<f32> for BoxedMat<N, M> {
+    type Output = BoxedMat<N, M>;
+
+    fn div(self, scalar: f32) -> BoxedMat<N, M> {
+        &self / scalar
+    }
+}
+
+
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<&BoxedMat<M, Z>> for &BoxedMat<N, M> {
+    type Output = BoxedMat<N, Z>;
+
+    fn mul(self, other: &BoxedMat<M, Z>) -> BoxedMat<N, Z> {
+        let mut new_mat = BoxedMat::new();
+        gemm(1.0, &*self.mat, &*other.mat, 0.0, &mut *new_mat.mat);
+        new_mat
+    }
+}
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<BoxedMat<M, Z>> for &BoxedMat<N, M> {
+    type Output = BoxedMat<N, Z>;
+
+    fn mul(self, other: BoxedMat<M, Z>) -> BoxedMat<N, Z> {
+        self * &other
+    }
+}
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<&BoxedMat<M, Z>> for BoxedMat<N, M> {
+    type Output = BoxedMat<N, Z>;
+
+    fn mul(self, other: &BoxedMat<M, Z>) -> BoxedMat<N, Z> {
+        &self * other
+    }
+}
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<BoxedMat<M, Z>> for BoxedMat<N, M> {
+    type Output = BoxedMat<N, Z>;
+
+    fn mul(self, other: BoxedMat<M, Z>) -> BoxedMat<N, Z> {
+        &self * &other
+    }
+}
+
+impl<const N: usize, const M: usize> Mul<&VecN<M>> for &BoxedMat<N, M> {
+    type Output = VecN<N>;
+
+    fn mul(self, other: &VecN<M>) -> VecN<N> {
+        &*self.mat * other
+    }
+}
+
+impl<const N: usize, const M: usize> Mul<VecN<M>> for &BoxedMat<N, M> {
+    type Output = VecN<N>;
+
+    fn mul(self, other: VecN<M>) -> VecN<N> {
+        &*self.mat * other
+    }
+}
+
+impl<const N: usize, const M: usize> Mul<&VecN<M>> for BoxedMat<N, M> {
+    type Output = VecN<N>;
+
+    fn mul(self, other: &VecN<M>) -> VecN<N> {
+        &*self.mat * other
+    }
+}
+
+impl<const N: usize, const M: usize> Mul<VecN<M>> for BoxedMat<N, M> {
+    type Output = VecN<N>;
+
+    fn mul(self, other: VecN<M>) -> VecN<N> {
+        &*self.mat * other
+    }
+}
@@ -0,0 +1,120 @@
+use core::mem::{align_of, size_of, size_of_val};
+use core::slice;
+use crate::vectors::VecN;
+use crate::matrixs::Mat;
+use crate::col_matrixs::ColMat;
+use crate::errors::MatrixError;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Types that are nothing but f32s with no padding, so any bytes of the right length and
+/// alignment are a valid value.
+///
+/// # Safety
+/// as_bytes and from_bytes trust this, implementing it for a type with padding or with
+/// fields that are not f32 is undefined behavior.
+pub unsafe trait Plain: Sized {}
+
+unsafe impl Plain for f32 {}
+unsafe impl<const N: usize> Plain for VecN<N> {}
+unsafe impl<const N: usize, const M: usize> Plain for Mat<N, M> {}
+unsafe impl<const N: usize, const M: usize> Plain for ColMat<N, M> {}
+unsafe impl Plain for PaddedVec3 {}
+unsafe impl Plain for PaddedMat3 {}
+
+// Raw bytes in native endianness, ready to upload or write out
+pub fn as_bytes<T: Plain>(vals: &[T]) -> &[u8] {
+    unsafe { slice::from_raw_parts(vals.as_ptr() as *const u8, size_of_val(vals)) }
+}
+
+// Fails if the bytes are not aligned for T or do not hold a whole number of values
+pub fn from_bytes<T: Plain>(bytes: &[u8]) -> Result<&[T], MatrixError> {
+    let len = check_bytes::<T>(bytes.as_ptr(), bytes.len())?;
+    Ok(unsafe { slice::from_raw_parts(bytes.as_ptr() as *const T, len) })
+}
+
+pub fn from_bytes_mut<T: Plain>(bytes: &mut [u8]) -> Result<&mut [T], MatrixError> {
+    let len = check_bytes::<T>(bytes.as_ptr(), bytes.len())?;
+    Ok(unsafe { slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut T, len) })
+}
+
+// Number of T in the bytes
+fn check_bytes<T>(ptr: *const u8, len: usize) -> Result<usize, MatrixError> {
+    let size = size_of::<T>();
+    if size == 0 {
+        return Ok(0);
+    }
+    // Alignments are always powers of two
+    if ptr as usize & (align_of::<T>() - 1) != 0 {
+        return Err(MatrixError::Misaligned {alignment: align_of::<T>()});
+    }
+    let count = len / size;
+    if count * size != len {
+        return Err(MatrixError::ByteLength {element_size: size, found: len});
+    }
+    Ok(count)
+}
+
+// A vec3 the way GLSL lays it out in uniform (std140) and storage (std430) buffers. Both
+// layouts give vec3 a 16 byte alignment, so arrays of them and the columns of a mat3 are
+// padded out to 16 bytes in either one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C, align(16))]
+pub struct PaddedVec3 {
+    pub vals: VecN<3>,
+    pad: f32,
+}
+
+// A mat3 in std140 or std430, three padded columns
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct PaddedMat3 {
+    pub columns: [PaddedVec3; 3],
+}
+
+impl PaddedVec3 {
+    pub fn new(vals: VecN<3>) -> PaddedVec3 {
+        PaddedVec3 {vals, pad: 0.0}
+    }
+}
+
+// Array of vec3 ready to go in a std140 or std430 buffer
+#[cfg(feature = "alloc")]
+pub fn pad_vec3s(vals: &[VecN<3>]) -> Vec<PaddedVec3> {
+    vals.iter().map(PaddedVec3::from).collect()
+}
+
+impl From<&VecN<3>> for PaddedVec3 {
+    fn from(vals: &VecN<3>) -> PaddedVec3 {
+        PaddedVec3::new(*vals)
+    }
+}
+
+impl From<&PaddedVec3> for VecN<3> {
+    fn from(padded: &PaddedVec3) -> VecN<3> {
+        padded.vals
+    }
+}
+
+// GLSL matrices are column major so the columns of mat end up one after the other
+impl From<&Mat<3,3>> for PaddedMat3 {
+    fn from(mat: &Mat<3,3>) -> PaddedMat3 {
+        let mut columns = [PaddedVec3::new(VecN::new()); 3];
+        for (j, column) in columns.iter_mut().enumerate() {
+            column.vals = VecN::from([mat[0][j], mat[1][j], mat[2][j]]);
+        }
+        PaddedMat3 {columns}
+    }
+}
+
+impl From<&PaddedMat3> for Mat<3,3> {
+    fn from(padded: &PaddedMat3) -> Mat<3,3> {
+        let mut mat = Mat::new();
+        for (j, column) in padded.columns.iter().enumerate() {
+            for i in 0..3 {
+                mat[i][j] = column.vals[i];
+            }
+        }
+        mat
+    }
+}
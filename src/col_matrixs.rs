@@ -0,0 +1,185 @@
+use core::ops::{Mul,Index,IndexMut};
+use crate::matrixs::Mat;
+use crate::vectors::VecN;
+use crate::errors::MatrixError;
+
+// N x M matrix stored column by column, the layout OpenGL, Vulkan and Fortran expect.
+// A row major M x N matrix has exactly the same memory as a column major N x M one, so
+// this is a Mat<M, N> whose rows are read as columns and moving between the two with
+// from_transposed and into_transposed costs nothing. Converting with From keeps the
+// same matrix and reorders the values.
+#[derive(Clone, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct ColMat<const N: usize, const M: usize> {
+    columns: Mat<M, N>,
+}
+
+pub type ColMat2 = ColMat<2, 2>;
+pub type ColMat3 = ColMat<3, 3>;
+pub type ColMat4 = ColMat<4, 4>;
+
+impl<const N: usize, const M: usize> ColMat<N, M> {
+    pub const fn new() -> ColMat<N, M> {
+        ColMat {columns: Mat::new()}
+    }
+
+    pub fn from_row_major_slice(vals: &[f32]) -> Result<ColMat<N, M>, MatrixError> {
+        Ok(ColMat {columns: Mat::from_column_major_slice(vals)?})
+    }
+
+    pub fn from_column_major_slice(vals: &[f32]) -> Result<ColMat<N, M>, MatrixError> {
+        Ok(ColMat {columns: Mat::from_row_major_slice(vals)?})
+    }
+
+    // All the values in column major order
+    pub fn as_slice(&self) -> &[f32] {
+        self.columns.as_slice()
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [f32] {
+        self.columns.as_mut_slice()
+    }
+
+    pub fn column(&self, j: usize) -> &VecN<N> {
+        &self.columns[j]
+    }
+
+    pub fn column_mut(&mut self, j: usize) -> &mut VecN<N> {
+        &mut self.columns[j]
+    }
+
+    // Element at row i and column j, None when out of bounds
+    pub const fn get(&self, i: usize, j: usize) -> Option<&f32> {
+        self.columns.get(j, i)
+    }
+
+    pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut f32> {
+        self.columns.get_mut(j, i)
+    }
+
+    // The row major transpose shares this memory so neither direction copies anything
+    pub const fn from_transposed(transposed: Mat<M, N>) -> ColMat<N, M> {
+        ColMat {columns: transposed}
+    }
+
+    pub fn into_transposed(self) -> Mat<M, N> {
+        self.columns
+    }
+
+    pub fn transpose(&self) -> ColMat<M, N> {
+        ColMat {columns: self.columns.transpose()}
+    }
+
+    pub const fn transpose_const(&self) -> ColMat<M, N> {
+        ColMat {columns: self.columns.transpose_const()}
+    }
+}
+
+impl<const N: usize, const M: usize> Default for ColMat<N, M> {
+    fn default() -> ColMat<N, M> {
+        ColMat::new()
+    }
+}
+
+impl<const N: usize> ColMat<N, N> {
+    pub const fn identity() -> ColMat<N, N> {
+        ColMat {columns: Mat::identity()}
+    }
+}
+
+impl<const N: usize, const M: usize> Index<(usize, usize)> for ColMat<N, M> {
+    type Output = f32;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f32 {
+        &self.columns[j][i]
+    }
+}
+
+impl<const N: usize, const M: usize> IndexMut<(usize, usize)> for ColMat<N, M> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f32 {
+        &mut self.columns[j][i]
+    }
+}
+
+// Sum of the columns weighted by the vector, which is the natural order for this layout
+impl<const N: usize, const M: usize> Mul<VecN<M>> for &ColMat<N, M> {
+    type Output = VecN<N>;
+
+    fn mul(self, other: VecN<M>) -> VecN<N> {
+        let mut new_vec = VecN::new();
+        for j in 0..M {
+            new_vec = new_vec + self.columns[j] * other[j];
+        }
+        new_vec
+    }
+}
+
+impl<const N: usize, const M: usize> Mul<&VecN<M>> for &ColMat<N, M> {
+    type Output = VecN<N>;
+
+    fn mul(self, other: &VecN<M>) -> VecN<N> {
+        self * *other
+    }
+}
+
+impl<const N: usize, const M: usize> Mul<&VecN<M>> for ColMat<N, M> {
+    type Output = VecN<N>;
+
+    fn mul(self, other: &VecN<M>) -> VecN<N> {
+        &self * *other
+    }
+}
+
+impl<const N: usize, const M: usize> Mul<VecN<M>> for ColMat<N, M> {
+    type Output = VecN<N>;
+
+    fn mul(self, other: VecN<M>) -> VecN<N> {
+        &self * other
+    }
+}
+
+// (AB)^T = B^T A^T, and the transposes are exactly what is stored, so this is one row
+// major product with no reordering
+impl<const N: usize, const M: usize, const Z: usize> Mul<&ColMat<M, Z>> for &ColMat<N, M> {
+    type Output = ColMat<N, Z>;
+
+    fn mul(self, other: &ColMat<M, Z>) -> ColMat<N, Z> {
+        ColMat {columns: &other.columns * &self.columns}
+    }
+}
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<ColMat<M, Z>> for &ColMat<N, M> {
+    type Output = ColMat<N, Z>;
+
+    fn mul(self, other: ColMat<M, Z>) -> ColMat<N, Z> {
+        self * &other
+    }
+}
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<&ColMat<M, Z>> for ColMat<N, M> {
+    type Output = ColMat<N, Z>;
+
+    fn mul(self, other: &ColMat<M, Z>) -> ColMat<N, Z> {
+        &self * other
+    }
+}
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<ColMat<M, Z>> for ColMat<N, M> {
+    type Output = ColMat<N, Z>;
+
+    fn mul(self, other: ColMat<M, Z>) -> ColMat<N, Z> {
+        &self * &other
+    }
+}
+
+impl<const N: usize, const M: usize> From<&Mat<N, M>> for ColMat<N, M> {
+    fn from(mat: &Mat<N, M>) -> ColMat<N, M> {
+        ColMat {columns: mat.transpose()}
+    }
+}
+
+impl<const N: usize, const M: usize> From<&ColMat<N, M>> for Mat<N, M> {
+    fn from(mat: &ColMat<N, M>) -> Mat<N, M> {
+        mat.columns.transpose()
+    }
+}
@@ -0,0 +1,145 @@
+use crate::vectors::VecN;
+use crate::matrixs::Mat;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+
+// Physics puts the polar angle (from the up axis) second and the azimuth third,
+// (r, theta, phi) = (radius, polar, azimuth). Math swaps the two angles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AngleConvention {
+    Physics,
+    Math,
+}
+
+// With Z up the azimuth is measured in the xy plane from +x towards +y.
+// With Y up it is measured in the zx plane from +z towards +x, which keeps the
+// same handedness and matches most graphics code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpAxis {
+    Y,
+    Z,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SphericalConvention {
+    pub angles: AngleConvention,
+    pub up: UpAxis,
+}
+
+impl SphericalConvention {
+    pub fn new(angles: AngleConvention, up: UpAxis) -> SphericalConvention {
+        SphericalConvention {angles, up}
+    }
+}
+
+// (a, b, up) where a and b span the ground plane
+fn to_local(vec: &VecN<3>, up: UpAxis) -> [f32; 3] {
+    match up {
+        UpAxis::Z => [vec[0], vec[1], vec[2]],
+        UpAxis::Y => [vec[2], vec[0], vec[1]],
+    }
+}
+
+fn from_local(local: [f32; 3], up: UpAxis) -> VecN<3> {
+    match up {
+        UpAxis::Z => VecN::from(local),
+        UpAxis::Y => VecN::from([local[1], local[2], local[0]]),
+    }
+}
+
+// Jacobian rows are in local order, this moves them back to x, y, z
+fn rows_from_local(rows: [[f32; 3]; 3], up: UpAxis) -> Mat<3,3> {
+    match up {
+        UpAxis::Z => Mat::from(rows),
+        UpAxis::Y => Mat::from([rows[1], rows[2], rows[0]]),
+    }
+}
+
+impl VecN<2> {
+    // (r, theta) with theta in (-pi, pi], the inverse of VecN::angle scaled by r
+    pub fn to_polar(&self) -> VecN<2> {
+        VecN::from([self.length(), f32::atan2(self[1], self[0])])
+    }
+
+    pub fn from_polar(polar: &VecN<2>) -> VecN<2> {
+        VecN::angle(polar[1]) * polar[0]
+    }
+
+    // d(x, y) / d(r, theta)
+    pub fn polar_jacobian(polar: &VecN<2>) -> Mat<2,2> {
+        let (r, theta) = (polar[0], polar[1]);
+        let (sin, cos) = f32::sin_cos(theta);
+        Mat::from([[cos, -r * sin],
+                    [sin, r * cos]])
+    }
+}
+
+impl VecN<3> {
+    // Polar angle is in [0, pi] and azimuth in (-pi, pi], both are 0 at the origin
+    pub fn to_spherical(&self, convention: SphericalConvention) -> VecN<3> {
+        let [a, b, up] = to_local(self, convention.up);
+        let r = self.length();
+        let polar = if r == 0.0 { 0.0 } else { f32::acos((up / r).clamp(-1.0, 1.0)) };
+        let azimuth = f32::atan2(b, a);
+        match convention.angles {
+            AngleConvention::Physics => VecN::from([r, polar, azimuth]),
+            AngleConvention::Math => VecN::from([r, azimuth, polar]),
+        }
+    }
+
+    pub fn from_spherical(spherical: &VecN<3>, convention: SphericalConvention) -> VecN<3> {
+        let (r, polar, azimuth) = split_angles(spherical, convention.angles);
+        let (sin_p, cos_p) = f32::sin_cos(polar);
+        let (sin_a, cos_a) = f32::sin_cos(azimuth);
+        from_local([r * sin_p * cos_a, r * sin_p * sin_a, r * cos_p], convention.up)
+    }
+
+    // d(x, y, z) / d(spherical) with the columns in the same order as the spherical coordinates
+    pub fn spherical_jacobian(spherical: &VecN<3>, convention: SphericalConvention) -> Mat<3,3> {
+        let (r, polar, azimuth) = split_angles(spherical, convention.angles);
+        let (sin_p, cos_p) = f32::sin_cos(polar);
+        let (sin_a, cos_a) = f32::sin_cos(azimuth);
+
+        let d_r = [sin_p * cos_a, sin_p * sin_a, cos_p];
+        let d_polar = [r * cos_p * cos_a, r * cos_p * sin_a, -r * sin_p];
+        let d_azimuth = [-r * sin_p * sin_a, r * sin_p * cos_a, 0.0];
+        let cols = match convention.angles {
+            AngleConvention::Physics => [d_r, d_polar, d_azimuth],
+            AngleConvention::Math => [d_r, d_azimuth, d_polar],
+        };
+        let mut rows = [[0.0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            *row = [cols[0][i], cols[1][i], cols[2][i]];
+        }
+        rows_from_local(rows, convention.up)
+    }
+
+    // (radius from the up axis, azimuth, height along the up axis)
+    pub fn to_cylindrical(&self, up: UpAxis) -> VecN<3> {
+        let [a, b, height] = to_local(self, up);
+        VecN::from([f32::hypot(a, b), f32::atan2(b, a), height])
+    }
+
+    pub fn from_cylindrical(cylindrical: &VecN<3>, up: UpAxis) -> VecN<3> {
+        let (rho, azimuth, height) = (cylindrical[0], cylindrical[1], cylindrical[2]);
+        let (sin, cos) = f32::sin_cos(azimuth);
+        from_local([rho * cos, rho * sin, height], up)
+    }
+
+    // d(x, y, z) / d(rho, azimuth, height)
+    pub fn cylindrical_jacobian(cylindrical: &VecN<3>, up: UpAxis) -> Mat<3,3> {
+        let (rho, azimuth) = (cylindrical[0], cylindrical[1]);
+        let (sin, cos) = f32::sin_cos(azimuth);
+        rows_from_local([[cos, -rho * sin, 0.0],
+                         [sin, rho * cos, 0.0],
+                         [0.0, 0.0, 1.0]], up)
+    }
+}
+
+// (r, polar, azimuth) regardless of the order they are stored in
+fn split_angles(spherical: &VecN<3>, angles: AngleConvention) -> (f32, f32, f32) {
+    match angles {
+        AngleConvention::Physics => (spherical[0], spherical[1], spherical[2]),
+        AngleConvention::Math => (spherical[0], spherical[2], spherical[1]),
+    }
+}
@@ -0,0 +1,390 @@
+use crate::vectors::VecN;
+#[cfg(feature = "alloc")]
+use crate::errors::MatrixError;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+// Anything that can be sampled along a parameter. Arc length and flattening
+// are built on top of eval and derivative so every curve gets them for free.
+pub trait Curve<const N: usize> {
+    fn eval(&self, t: f32) -> VecN<N>;
+
+    fn derivative(&self, t: f32) -> VecN<N>;
+
+    // Range of valid t values
+    fn domain(&self) -> (f32, f32) {
+        (0.0, 1.0)
+    }
+
+    // Length of the curve measured along a polyline with the given number of segments
+    fn arc_length(&self, samples: usize) -> f32 {
+        let samples = samples.max(1);
+        let (start, end) = self.domain();
+        let mut prev = self.eval(start);
+        let mut total = 0.0;
+        for i in 0..=samples {
+            let t = start + (end - start) * i as f32 / samples as f32;
+            let point = self.eval(t);
+            total += prev.dist(&point);
+            prev = point;
+        }
+        total
+    }
+
+    #[cfg(feature = "alloc")]
+    fn arc_length_table(&self, samples: usize) -> ArcLengthTable {
+        let samples = samples.max(1);
+        let (start, end) = self.domain();
+        let mut params = Vec::with_capacity(samples + 1);
+        let mut lengths = Vec::with_capacity(samples + 1);
+
+        let mut prev = self.eval(start);
+        let mut total = 0.0;
+        for i in 0..=samples {
+            let t = start + (end - start) * i as f32 / samples as f32;
+            let point = self.eval(t);
+            total += prev.dist(&point);
+            prev = point;
+            params.push(t);
+            lengths.push(total);
+        }
+        ArcLengthTable {params, lengths}
+    }
+
+    // Point at distance s along the curve, clamped to the ends
+    #[cfg(feature = "alloc")]
+    fn eval_at_length(&self, table: &ArcLengthTable, s: f32) -> VecN<N> {
+        self.eval(table.param_at_length(s))
+    }
+
+    // Splits the curve until each piece is within tolerance of a straight line,
+    // returns the points of the resulting polyline including both ends
+    #[cfg(feature = "alloc")]
+    fn flatten(&self, tolerance: f32) -> Vec<VecN<N>> {
+        let (start, end) = self.domain();
+        let mut points = vec![self.eval(start)];
+        flatten_range(self, start, end, tolerance, 0, &mut points);
+        points
+    }
+}
+
+#[cfg(feature = "alloc")]
+const MIN_DEPTH: usize = 3;
+#[cfg(feature = "alloc")]
+const MAX_DEPTH: usize = 16;
+
+#[cfg(feature = "alloc")]
+fn flatten_range<C: Curve<N> + ?Sized, const N: usize>(curve: &C, start: f32, end: f32, tolerance: f32, depth: usize, points: &mut Vec<VecN<N>>) {
+    let a = curve.eval(start);
+    let b = curve.eval(end);
+    let mid_t = (start + end) / 2.0;
+    let mid = curve.eval(mid_t);
+
+    // Distance of the midpoint from the chord between the ends
+    let chord = b - a;
+    let offset = mid - a;
+    let length_sq = chord * chord;
+    let deviation = if length_sq == 0.0 {
+        offset.length()
+    } else {
+        (offset - chord * (offset * chord / length_sq)).length()
+    };
+
+    if depth >= MAX_DEPTH || (depth >= MIN_DEPTH && deviation <= tolerance) {
+        points.push(b);
+        return;
+    }
+    flatten_range(curve, start, mid_t, tolerance, depth + 1, points);
+    flatten_range(curve, mid_t, end, tolerance, depth + 1, points);
+}
+
+// Cumulative lengths at evenly spaced parameters, used to walk a curve at constant speed
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct ArcLengthTable {
+    params: Vec<f32>,
+    lengths: Vec<f32>,
+}
+
+#[cfg(feature = "alloc")]
+impl ArcLengthTable {
+    pub fn length(&self) -> f32 {
+        *self.lengths.last().unwrap()
+    }
+
+    pub fn param_at_length(&self, s: f32) -> f32 {
+        let s = s.clamp(0.0, self.length());
+        let i = self.lengths.partition_point(|&length| length < s);
+        if i == 0 {
+            return self.params[0];
+        }
+
+        let (l0, l1) = (self.lengths[i - 1], self.lengths[i]);
+        let (t0, t1) = (self.params[i - 1], self.params[i]);
+        if l1 == l0 {
+            t0
+        } else {
+            t0 + (t1 - t0) * (s - l0) / (l1 - l0)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuadraticBezier<const N: usize> {
+    pub p0: VecN<N>,
+    pub p1: VecN<N>,
+    pub p2: VecN<N>,
+}
+
+impl<const N: usize> QuadraticBezier<N> {
+    pub fn new(p0: VecN<N>, p1: VecN<N>, p2: VecN<N>) -> QuadraticBezier<N> {
+        QuadraticBezier {p0, p1, p2}
+    }
+}
+
+impl<const N: usize> Curve<N> for QuadraticBezier<N> {
+    fn eval(&self, t: f32) -> VecN<N> {
+        let u = 1.0 - t;
+        self.p0 * (u * u) + self.p1 * (2.0 * u * t) + self.p2 * (t * t)
+    }
+
+    fn derivative(&self, t: f32) -> VecN<N> {
+        (self.p1 - self.p0) * (2.0 * (1.0 - t)) + (self.p2 - self.p1) * (2.0 * t)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier<const N: usize> {
+    pub p0: VecN<N>,
+    pub p1: VecN<N>,
+    pub p2: VecN<N>,
+    pub p3: VecN<N>,
+}
+
+impl<const N: usize> CubicBezier<N> {
+    pub fn new(p0: VecN<N>, p1: VecN<N>, p2: VecN<N>, p3: VecN<N>) -> CubicBezier<N> {
+        CubicBezier {p0, p1, p2, p3}
+    }
+
+    // De Casteljau split into the parts before and after t
+    pub fn split(&self, t: f32) -> (CubicBezier<N>, CubicBezier<N>) {
+        let p01 = self.p0.lerp(&self.p1, t);
+        let p12 = self.p1.lerp(&self.p2, t);
+        let p23 = self.p2.lerp(&self.p3, t);
+        let p012 = p01.lerp(&p12, t);
+        let p123 = p12.lerp(&p23, t);
+        let mid = p012.lerp(&p123, t);
+        (CubicBezier::new(self.p0, p01, p012, mid), CubicBezier::new(mid, p123, p23, self.p3))
+    }
+}
+
+impl<const N: usize> Curve<N> for CubicBezier<N> {
+    fn eval(&self, t: f32) -> VecN<N> {
+        let u = 1.0 - t;
+        self.p0 * (u * u * u) + self.p1 * (3.0 * u * u * t) + self.p2 * (3.0 * u * t * t) + self.p3 * (t * t * t)
+    }
+
+    fn derivative(&self, t: f32) -> VecN<N> {
+        let u = 1.0 - t;
+        (self.p1 - self.p0) * (3.0 * u * u) + (self.p2 - self.p1) * (6.0 * u * t) + (self.p3 - self.p2) * (3.0 * t * t)
+    }
+}
+
+// Cubic through two points with the given tangents at each end
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hermite<const N: usize> {
+    pub p0: VecN<N>,
+    pub m0: VecN<N>,
+    pub p1: VecN<N>,
+    pub m1: VecN<N>,
+}
+
+impl<const N: usize> Hermite<N> {
+    pub fn new(p0: VecN<N>, m0: VecN<N>, p1: VecN<N>, m1: VecN<N>) -> Hermite<N> {
+        Hermite {p0, m0, p1, m1}
+    }
+}
+
+impl<const N: usize> Curve<N> for Hermite<N> {
+    fn eval(&self, t: f32) -> VecN<N> {
+        let t2 = t * t;
+        let t3 = t2 * t;
+        self.p0 * (2.0 * t3 - 3.0 * t2 + 1.0) + self.m0 * (t3 - 2.0 * t2 + t)
+            + self.p1 * (-2.0 * t3 + 3.0 * t2) + self.m1 * (t3 - t2)
+    }
+
+    fn derivative(&self, t: f32) -> VecN<N> {
+        let t2 = t * t;
+        self.p0 * (6.0 * t2 - 6.0 * t) + self.m0 * (3.0 * t2 - 4.0 * t + 1.0)
+            + self.p1 * (-6.0 * t2 + 6.0 * t) + self.m1 * (3.0 * t2 - 2.0 * t)
+    }
+}
+
+// Uniform Catmull-Rom spline. It passes through every point except the first and last,
+// which only shape the ends. t runs from 0 to points.len() - 3, one unit per segment.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatmullRom<const N: usize> {
+    points: Vec<VecN<N>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> CatmullRom<N> {
+    // Fails if there are fewer than 4 points
+    pub fn new(points: Vec<VecN<N>>) -> Result<CatmullRom<N>, MatrixError> {
+        if points.len() < 4 {
+            return Err(MatrixError::TooFewPoints {needed: 4, found: points.len()});
+        }
+        Ok(CatmullRom {points})
+    }
+
+    pub fn points(&self) -> &[VecN<N>] {
+        &self.points
+    }
+
+    fn segment(&self, t: f32) -> (usize, f32) {
+        let segments = self.points.len() - 3;
+        let i = (t.max(0.0) as usize).min(segments - 1);
+        (i, t - i as f32)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> Curve<N> for CatmullRom<N> {
+    fn eval(&self, t: f32) -> VecN<N> {
+        let (i, u) = self.segment(t);
+        let [p0, p1, p2, p3] = [self.points[i], self.points[i + 1], self.points[i + 2], self.points[i + 3]];
+        (p1 * 2.0 + (p2 - p0) * u + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * (u * u)
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * (u * u * u)) * 0.5
+    }
+
+    fn derivative(&self, t: f32) -> VecN<N> {
+        let (i, u) = self.segment(t);
+        let [p0, p1, p2, p3] = [self.points[i], self.points[i + 1], self.points[i + 2], self.points[i + 3]];
+        ((p2 - p0) + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * (2.0 * u)
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * (3.0 * u * u)) * 0.5
+    }
+
+    fn domain(&self) -> (f32, f32) {
+        (0.0, (self.points.len() - 3) as f32)
+    }
+}
+
+// B-spline of any degree over a knot vector, evaluated with de Boor's algorithm
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BSpline<const N: usize> {
+    degree: usize,
+    points: Vec<VecN<N>>,
+    knots: Vec<f32>,
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> BSpline<N> {
+    // Needs more than degree points and points.len() + degree + 1 non decreasing knots
+    pub fn new(degree: usize, points: Vec<VecN<N>>, knots: Vec<f32>) -> Result<BSpline<N>, MatrixError> {
+        if points.len() <= degree {
+            return Err(MatrixError::TooFewPoints {needed: degree + 1, found: points.len()});
+        }
+        if knots.len() != points.len() + degree + 1 {
+            return Err(MatrixError::DimensionMismatch {expected: points.len() + degree + 1, found: knots.len()});
+        }
+        if let Some(index) = knots.windows(2).position(|pair| pair[0] > pair[1]) {
+            return Err(MatrixError::DecreasingKnots {index: index + 1});
+        }
+        Ok(BSpline {degree, points, knots})
+    }
+
+    // Evenly spaced knots 0, 1, 2, ... so the curve does not touch the end points
+    pub fn uniform(degree: usize, points: Vec<VecN<N>>) -> Result<BSpline<N>, MatrixError> {
+        let knots = (0..points.len() + degree + 1).map(|i| i as f32).collect();
+        BSpline::new(degree, points, knots)
+    }
+
+    // Evenly spaced inner knots with the ends repeated so the curve starts and ends on the end points
+    pub fn clamped(degree: usize, points: Vec<VecN<N>>) -> Result<BSpline<N>, MatrixError> {
+        if points.len() <= degree {
+            return Err(MatrixError::TooFewPoints {needed: degree + 1, found: points.len()});
+        }
+        let inner = points.len() - degree;
+        let mut knots = vec![0.0; degree];
+        knots.extend((0..=inner).map(|i| i as f32 / inner as f32));
+        knots.extend(vec![1.0; degree]);
+        BSpline::new(degree, points, knots)
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn points(&self) -> &[VecN<N>] {
+        &self.points
+    }
+
+    pub fn knots(&self) -> &[f32] {
+        &self.knots
+    }
+
+    // Index k such that knots[k] <= t < knots[k + 1] inside the domain
+    fn span(&self, t: f32) -> usize {
+        let last = self.points.len() - 1;
+        if t >= self.knots[last + 1] {
+            return last;
+        }
+        let mut k = self.degree;
+        while k < last && t >= self.knots[k + 1] {
+            k += 1;
+        }
+        k
+    }
+
+    // The derivative of a B-spline is a B-spline of one lower degree
+    fn derivative_spline(&self) -> Option<BSpline<N>> {
+        if self.degree == 0 {
+            return None;
+        }
+        let p = self.degree as f32;
+        let points = (0..self.points.len() - 1).map(|i| {
+            let span = self.knots[i + self.degree + 1] - self.knots[i + 1];
+            if span == 0.0 {
+                VecN::new()
+            } else {
+                (self.points[i + 1] - self.points[i]) * (p / span)
+            }
+        }).collect();
+        let knots = self.knots[1..self.knots.len() - 1].to_vec();
+        Some(BSpline {degree: self.degree - 1, points, knots})
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> Curve<N> for BSpline<N> {
+    fn eval(&self, t: f32) -> VecN<N> {
+        let (start, end) = self.domain();
+        let t = t.clamp(start, end);
+        let k = self.span(t);
+        let p = self.degree;
+
+        let mut d: Vec<VecN<N>> = (0..=p).map(|j| self.points[j + k - p]).collect();
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let i = j + k - p;
+                let denom = self.knots[i + p + 1 - r] - self.knots[i];
+                let alpha = if denom == 0.0 { 0.0 } else { (t - self.knots[i]) / denom };
+                d[j] = d[j - 1].lerp(&d[j], alpha);
+            }
+        }
+        d[p]
+    }
+
+    fn derivative(&self, t: f32) -> VecN<N> {
+        match self.derivative_spline() {
+            Some(spline) => spline.eval(t),
+            None => VecN::new(),
+        }
+    }
+
+    fn domain(&self) -> (f32, f32) {
+        (self.knots[self.degree], self.knots[self.points.len()])
+    }
+}
@@ -0,0 +1,51 @@
+use core::fmt;
+
+// Everything that can go wrong in the fallible parts of the crate
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatrixError {
+    Singular,
+    NotPositiveDefinite,
+    DimensionMismatch { expected: usize, found: usize },
+    NotConverged { iterations: usize, residual: f32 },
+    // Index of the first value that is not a number
+    ParseError { index: usize },
+    NonFinite,
+    // Index of the first input that depends on the ones before it
+    LinearlyDependent { index: usize },
+    // Byte casts, the start of the bytes is not aligned to alignment
+    Misaligned { alignment: usize },
+    // Byte casts, the length is not a whole number of element_size values
+    ByteLength { element_size: usize, found: usize },
+    // Curves and combinations that need a minimum number of points
+    TooFewPoints { needed: usize, found: usize },
+    // Index of the first knot that is smaller than the one before it
+    DecreasingKnots { index: usize },
+    // Affine combinations, the weights have to add up to one
+    WeightSum { sum: f32 },
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatrixError::Singular => write!(f, "matrix is singular"),
+            MatrixError::NotPositiveDefinite => write!(f, "matrix is not positive definite"),
+            MatrixError::DimensionMismatch { expected, found } =>
+                write!(f, "dimension mismatch, expected {} but found {}", expected, found),
+            MatrixError::NotConverged { iterations, residual } =>
+                write!(f, "did not converge after {} iterations, residual {}", iterations, residual),
+            MatrixError::ParseError { index } => write!(f, "value {} is not a number", index),
+            MatrixError::NonFinite => write!(f, "input contains NaN or infinite values"),
+            MatrixError::LinearlyDependent { index } => write!(f, "vector {} is linearly dependent", index),
+            MatrixError::Misaligned { alignment } => write!(f, "bytes are not aligned to {}", alignment),
+            MatrixError::ByteLength { element_size, found } =>
+                write!(f, "{} bytes is not a multiple of the element size {}", found, element_size),
+            MatrixError::TooFewPoints { needed, found } =>
+                write!(f, "needs at least {} points but found {}", needed, found),
+            MatrixError::DecreasingKnots { index } => write!(f, "knot {} is smaller than the one before it", index),
+            MatrixError::WeightSum { sum } => write!(f, "weights add up to {} instead of 1", sum),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MatrixError {}
@@ -0,0 +1,160 @@
+use crate::vectors::VecN;
+use crate::matrixs::Mat;
+use crate::quaternions::Quat;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+
+// Axes the three rotations are applied around, first to last
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EulerOrder {
+    XYZ, XZY, YXZ, YZX, ZXY, ZYX,
+    XYX, XZX, YXY, YZY, ZXZ, ZYZ,
+}
+
+// Intrinsic rotations happen around the axes of the already rotated frame,
+// extrinsic rotations happen around the fixed world axes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EulerFrame {
+    Intrinsic,
+    Extrinsic,
+}
+
+impl EulerOrder {
+    pub fn axes(&self) -> [usize; 3] {
+        match self {
+            EulerOrder::XYZ => [0, 1, 2],
+            EulerOrder::XZY => [0, 2, 1],
+            EulerOrder::YXZ => [1, 0, 2],
+            EulerOrder::YZX => [1, 2, 0],
+            EulerOrder::ZXY => [2, 0, 1],
+            EulerOrder::ZYX => [2, 1, 0],
+            EulerOrder::XYX => [0, 1, 0],
+            EulerOrder::XZX => [0, 2, 0],
+            EulerOrder::YXY => [1, 0, 1],
+            EulerOrder::YZY => [1, 2, 1],
+            EulerOrder::ZXZ => [2, 0, 2],
+            EulerOrder::ZYZ => [2, 1, 2],
+        }
+    }
+
+    pub fn reversed(&self) -> EulerOrder {
+        match self {
+            EulerOrder::XYZ => EulerOrder::ZYX,
+            EulerOrder::XZY => EulerOrder::YZX,
+            EulerOrder::YXZ => EulerOrder::ZXY,
+            EulerOrder::YZX => EulerOrder::XZY,
+            EulerOrder::ZXY => EulerOrder::YXZ,
+            EulerOrder::ZYX => EulerOrder::XYZ,
+            proper => *proper,
+        }
+    }
+
+    // True for the orders that repeat the first axis (ZXZ and friends)
+    pub fn is_proper(&self) -> bool {
+        let axes = self.axes();
+        axes[0] == axes[2]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EulerAngles {
+    pub angles: VecN<3>,
+    pub order: EulerOrder,
+    pub frame: EulerFrame,
+}
+
+impl EulerAngles {
+    pub fn new(first: f32, second: f32, third: f32, order: EulerOrder, frame: EulerFrame) -> EulerAngles {
+        EulerAngles {angles: VecN::from([first, second, third]), order, frame}
+    }
+
+    // The middle angle is kept in [-pi/2, pi/2] for Tait-Bryan orders and [0, pi] for proper orders.
+    // At gimbal lock the third angle is set to zero and the first one carries the whole rotation.
+    pub fn from_mat(mat: &Mat<3,3>, order: EulerOrder, frame: EulerFrame) -> EulerAngles {
+        let angles = match frame {
+            EulerFrame::Intrinsic => intrinsic_angles(mat, order, false),
+            EulerFrame::Extrinsic => {
+                // The reversed order lists the angles backwards, so its first angle is the one to zero
+                let angles = intrinsic_angles(mat, order.reversed(), true);
+                VecN::from([angles[2], angles[1], angles[0]])
+            }
+        };
+        EulerAngles {angles, order, frame}
+    }
+
+    pub fn to_mat(&self) -> Mat<3,3> {
+        let axes = self.order.axes();
+        let rotations = [axis_rotation(axes[0], self.angles[0]),
+                         axis_rotation(axes[1], self.angles[1]),
+                         axis_rotation(axes[2], self.angles[2])];
+        match self.frame {
+            EulerFrame::Intrinsic => &(&rotations[0] * &rotations[1]) * &rotations[2],
+            EulerFrame::Extrinsic => &(&rotations[2] * &rotations[1]) * &rotations[0],
+        }
+    }
+
+    // Same rotation expressed in another convention
+    pub fn convert(&self, order: EulerOrder, frame: EulerFrame) -> EulerAngles {
+        EulerAngles::from_mat(&self.to_mat(), order, frame)
+    }
+}
+
+fn axis_rotation(axis: usize, theta: f32) -> Mat<3,3> {
+    match axis {
+        0 => Mat::<3,3>::rotation_x(theta),
+        1 => Mat::<3,3>::rotation_y(theta),
+        _ => Mat::<3,3>::rotation_z(theta),
+    }
+}
+
+// Angles (a, b, c) such that mat = R_i(a) * R_j(b) * R_k(c). At gimbal lock c is zero,
+// or a when zero_first is set.
+fn intrinsic_angles(mat: &Mat<3,3>, order: EulerOrder, zero_first: bool) -> VecN<3> {
+    const LOCK_EPSILON: f32 = 1e-6;
+
+    let [i, j, last] = order.axes();
+    let k = 3 - i - j;
+    // +1 when (i, j, k) is a cyclic permutation of (x, y, z)
+    let sign = if (i + 1) % 3 == j { 1.0 } else { -1.0 };
+
+    let (b, locked) = if order.is_proper() {
+        let b = f32::acos(mat[i][i].clamp(-1.0, 1.0));
+        (b, f32::sin(b).abs() < LOCK_EPSILON)
+    } else {
+        let sin_b = (sign * mat[i][k]).clamp(-1.0, 1.0);
+        (f32::asin(sin_b), 1.0 - sin_b.abs() < LOCK_EPSILON)
+    };
+
+    if locked && zero_first {
+        // Row j only depends on the third angle once the first is zero
+        let m = 3 - j - last;
+        let last_sign = if (last + 1) % 3 == j { 1.0 } else { -1.0 };
+        let c = f32::atan2(-last_sign * mat[j][m], mat[j][j]);
+        return VecN::from([0.0, b, c]);
+    }
+    if locked {
+        // Column j only depends on the first angle once the third is zero
+        let a = f32::atan2(sign * mat[k][j], mat[j][j]);
+        return VecN::from([a, b, 0.0]);
+    }
+
+    let (a, c) = if order.is_proper() {
+        (f32::atan2(mat[j][i], -sign * mat[k][i]), f32::atan2(mat[i][j], sign * mat[i][k]))
+    } else {
+        (f32::atan2(-sign * mat[j][k], mat[k][k]), f32::atan2(-sign * mat[i][j], mat[i][i]))
+    };
+    VecN::from([a, b, c])
+}
+
+impl From<&EulerAngles> for Mat<3,3> {
+    fn from(angles: &EulerAngles) -> Mat<3,3> {
+        angles.to_mat()
+    }
+}
+
+impl From<&EulerAngles> for Quat {
+    fn from(angles: &EulerAngles) -> Quat {
+        Quat::from(&angles.to_mat())
+    }
+}
@@ -0,0 +1,358 @@
+use crate::vectors::{VecN, Unit};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+
+const EPSILON: f32 = 1e-6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: VecN<3>,
+    pub direction: VecN<3>,
+}
+
+impl Ray {
+    pub fn new(origin: VecN<3>, direction: VecN<3>) -> Ray {
+        Ray {origin, direction}
+    }
+
+    pub fn at(&self, t: f32) -> VecN<3> {
+        self.origin + self.direction * t
+    }
+
+    pub fn closest_point(&self, point: &VecN<3>) -> VecN<3> {
+        let t = (point - self.origin) * self.direction / (self.direction * self.direction);
+        self.at(t.max(0.0))
+    }
+
+    pub fn distance(&self, point: &VecN<3>) -> f32 {
+        self.closest_point(point).dist(point)
+    }
+
+    // All intersections return the ray parameter t of the first hit in front of the origin
+
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<f32> {
+        let denom = plane.normal * self.direction;
+        if denom.abs() < EPSILON {
+            return None;
+        }
+        let t = -plane.signed_distance(&self.origin) / denom;
+        if t >= 0.0 { Some(t) } else { None }
+    }
+
+    // Returns the exit point when the origin is inside the sphere
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        let offset = self.origin - sphere.center;
+        let a = self.direction * self.direction;
+        let half_b = offset * self.direction;
+        let c = offset * offset - sphere.radius * sphere.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let root = f32::sqrt(discriminant);
+        let near = (-half_b - root) / a;
+        let far = (-half_b + root) / a;
+        if near >= 0.0 {
+            Some(near)
+        } else if far >= 0.0 {
+            Some(far)
+        } else {
+            None
+        }
+    }
+
+    // Slab method, returns 0 when the origin is inside the box
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut t_min: f32 = 0.0;
+        let mut t_max = f32::INFINITY;
+        for i in 0..3 {
+            // Parallel to this slab, 0 * inf would give NaN for an origin on a face
+            if self.direction[i] == 0.0 {
+                if self.origin[i] < aabb.min[i] || self.origin[i] > aabb.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let inv = 1.0 / self.direction[i];
+            let t1 = (aabb.min[i] - self.origin[i]) * inv;
+            let t2 = (aabb.max[i] - self.origin[i]) * inv;
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+        }
+        if t_min <= t_max { Some(t_min) } else { None }
+    }
+
+    // Moller-Trumbore, both sides of the triangle count as a hit
+    pub fn intersect_triangle(&self, triangle: &Triangle) -> Option<f32> {
+        let edge1 = triangle.b - triangle.a;
+        let edge2 = triangle.c - triangle.a;
+        let p = self.direction.cross(&edge2);
+        let det = edge1 * p;
+        if det.abs() < EPSILON {
+            return None;
+        }
+
+        let inv_det = 1.0 / det;
+        let s = self.origin - triangle.a;
+        let u = s * p * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(&edge1);
+        let v = self.direction * q * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2 * q * inv_det;
+        if t >= 0.0 { Some(t) } else { None }
+    }
+}
+
+// All points x with normal * x = distance, the normal is kept unit length
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    pub normal: VecN<3>,
+    pub distance: f32,
+}
+
+impl Plane {
+    pub fn new(normal: VecN<3>, distance: f32) -> Plane {
+        let length = normal.length();
+        Plane {normal: normal / length, distance: distance / length}
+    }
+
+    // A zero normal has no plane of its own so it falls back to +z
+    pub fn from_point_normal(point: &VecN<3>, normal: &VecN<3>) -> Plane {
+        let normal = Unit::try_new(*normal, 0.0).unwrap_or_else(|| Unit::new_unchecked(VecN::from([0.0, 0.0, 1.0])));
+        Plane::from_point_unit_normal(point, &normal)
+    }
+
+    pub fn from_point_unit_normal(point: &VecN<3>, normal: &Unit<VecN<3>>) -> Plane {
+        Plane {normal: **normal, distance: **normal * point}
+    }
+
+    // Counter clockwise winding faces the normal, None if the points are collinear
+    pub fn from_points(a: &VecN<3>, b: &VecN<3>, c: &VecN<3>) -> Option<Plane> {
+        let normal = (b - a).cross(&(c - a));
+        if normal.length() < EPSILON {
+            return None;
+        }
+        Some(Plane::from_point_normal(a, &normal))
+    }
+
+    // Positive on the side the normal points to
+    pub fn signed_distance(&self, point: &VecN<3>) -> f32 {
+        self.normal * point - self.distance
+    }
+
+    pub fn distance(&self, point: &VecN<3>) -> f32 {
+        self.signed_distance(point).abs()
+    }
+
+    pub fn closest_point(&self, point: &VecN<3>) -> VecN<3> {
+        point - self.normal * self.signed_distance(point)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sphere {
+    pub center: VecN<3>,
+    pub radius: f32,
+}
+
+impl Sphere {
+    pub fn new(center: VecN<3>, radius: f32) -> Sphere {
+        Sphere {center, radius}
+    }
+
+    pub fn contains(&self, point: &VecN<3>) -> bool {
+        self.center.dist(point) <= self.radius
+    }
+
+    // Closest point on or in the sphere
+    pub fn closest_point(&self, point: &VecN<3>) -> VecN<3> {
+        if self.contains(point) {
+            return *point;
+        }
+        self.center + (point - self.center).normalize() * self.radius
+    }
+
+    // Zero for points inside the sphere
+    pub fn distance(&self, point: &VecN<3>) -> f32 {
+        (self.center.dist(point) - self.radius).max(0.0)
+    }
+
+    pub fn intersects_sphere(&self, other: &Sphere) -> bool {
+        self.center.dist(&other.center) <= self.radius + other.radius
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        aabb.distance(&self.center) <= self.radius
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: VecN<3>,
+    pub max: VecN<3>,
+}
+
+impl Aabb {
+    // The corners can be given in any order
+    pub fn new(a: VecN<3>, b: VecN<3>) -> Aabb {
+        let mut min = a;
+        let mut max = b;
+        for i in 0..3 {
+            min[i] = a[i].min(b[i]);
+            max[i] = a[i].max(b[i]);
+        }
+        Aabb {min, max}
+    }
+
+    // None for an empty slice
+    pub fn from_points(points: &[VecN<3>]) -> Option<Aabb> {
+        let first = points.first()?;
+        let mut aabb = Aabb {min: *first, max: *first};
+        for point in points {
+            for i in 0..3 {
+                aabb.min[i] = aabb.min[i].min(point[i]);
+                aabb.max[i] = aabb.max[i].max(point[i]);
+            }
+        }
+        Some(aabb)
+    }
+
+    pub fn center(&self) -> VecN<3> {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn extents(&self) -> VecN<3> {
+        self.max - self.min
+    }
+
+    pub fn contains(&self, point: &VecN<3>) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    pub fn closest_point(&self, point: &VecN<3>) -> VecN<3> {
+        let mut answer = *point;
+        for i in 0..3 {
+            answer[i] = point[i].clamp(self.min[i], self.max[i]);
+        }
+        answer
+    }
+
+    // Zero for points inside the box
+    pub fn distance(&self, point: &VecN<3>) -> f32 {
+        self.closest_point(point).dist(point)
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Triangle {
+    pub a: VecN<3>,
+    pub b: VecN<3>,
+    pub c: VecN<3>,
+}
+
+impl Triangle {
+    pub fn new(a: VecN<3>, b: VecN<3>, c: VecN<3>) -> Triangle {
+        Triangle {a, b, c}
+    }
+
+    // Counter clockwise winding faces the normal
+    pub fn normal(&self) -> VecN<3> {
+        (self.b - self.a).cross(&(self.c - self.a)).normalize()
+    }
+
+    pub fn area(&self) -> f32 {
+        (self.b - self.a).cross(&(self.c - self.a)).length() / 2.0
+    }
+
+    // Checks which voronoi region of the triangle the point falls in,
+    // from Real-Time Collision Detection by Christer Ericson
+    pub fn closest_point(&self, point: &VecN<3>) -> VecN<3> {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let ab = b - a;
+        let ac = c - a;
+
+        let ap = point - a;
+        let d1 = ab * ap;
+        let d2 = ac * ap;
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return a;
+        }
+
+        let bp = point - b;
+        let d3 = ab * bp;
+        let d4 = ac * bp;
+        if d3 >= 0.0 && d4 <= d3 {
+            return b;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = point - c;
+        let d5 = ab * cp;
+        let d6 = ac * cp;
+        if d6 >= 0.0 && d5 <= d6 {
+            return c;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        let denom = 1.0 / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+
+    pub fn distance(&self, point: &VecN<3>) -> f32 {
+        self.closest_point(point).dist(point)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineSegment {
+    pub start: VecN<3>,
+    pub end: VecN<3>,
+}
+
+impl LineSegment {
+    pub fn new(start: VecN<3>, end: VecN<3>) -> LineSegment {
+        LineSegment {start, end}
+    }
+
+    pub fn length(&self) -> f32 {
+        self.start.dist(&self.end)
+    }
+
+    pub fn closest_point(&self, point: &VecN<3>) -> VecN<3> {
+        let dir = self.end - self.start;
+        let length_sq = dir * dir;
+        if length_sq < EPSILON * EPSILON {
+            return self.start;
+        }
+        let t = ((point - self.start) * dir / length_sq).clamp(0.0, 1.0);
+        self.start + dir * t
+    }
+
+    pub fn distance(&self, point: &VecN<3>) -> f32 {
+        self.closest_point(point).dist(point)
+    }
+}
@@ -1,5 +1,28 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("without the std feature the libm feature is needed for the float functions");
+
 pub mod vectors;
 pub mod matrixs;
+pub mod quaternions;
+pub mod euler_angles;
+pub mod affines;
+pub mod points;
+pub mod geometry;
+pub mod polygons;
+pub mod curves;
+pub mod coordinates;
+pub mod errors;
+pub mod simd;
+#[cfg(feature = "alloc")]
+pub mod boxed_matrixs;
+pub mod col_matrixs;
+pub mod bytes;
+mod math;
 
 
 
@@ -4,7 +4,11 @@ fn main() {
 
 #[cfg(test)]
 mod vector_tests {
-    use my_matrix_lib::vectors::VecN;
+    use my_matrix_lib::vectors::{VecN, Vec2, Vec3, Vec4, Unit};
+    use my_matrix_lib::matrixs::{Mat, Mat3, Mat4};
+    use my_matrix_lib::quaternions::Quat;
+    use my_matrix_lib::geometry::Plane;
+    use my_matrix_lib::errors::MatrixError;
 
     #[test]
     fn vector_contains_values() {
@@ -57,6 +61,155 @@ mod vector_tests {
         assert_eq!(x.cross(&y), VecN::from([25.2,-21.8,-3.0]));
     }
 
+    #[test]
+    fn vector_safe_normalize() {
+        let x = VecN::from([0.0, 3.0, 4.0]);
+        let zero = VecN::<3>::new();
+        assert_eq!(x.try_normalize(1e-6), Some(VecN::from([0.0, 0.6, 0.8])));
+        assert_eq!(zero.try_normalize(1e-6), None);
+        assert_eq!(VecN::from([1e-7, 0.0]).try_normalize(1e-6), None);
+        assert_eq!(zero.normalize_or(VecN::from([1.0, 0.0, 0.0])), VecN::from([1.0, 0.0, 0.0]));
+        assert_eq!(x.normalize_or(VecN::from([1.0, 0.0, 0.0])), VecN::from([0.0, 0.6, 0.8]));
+        assert_eq!(x.try_project(&VecN::from([0.0, 0.0, 2.0]), 1e-6), Some(VecN::from([0.0, 0.0, 4.0])));
+        assert_eq!(x.try_project(&zero, 1e-6), None);
+    }
+
+    #[test]
+    fn vector_components_and_swizzles() {
+        let mut v = Vec4::from([1.0, 2.0, 3.0, 4.0]);
+        assert_eq!((v.x(), v.y(), v.z(), v.w()), (1.0, 2.0, 3.0, 4.0));
+        v.set_y(-2.0);
+        v.set_w(0.5);
+        assert_eq!(v, VecN::from([1.0, -2.0, 3.0, 0.5]));
+        assert_eq!(v.xy(), Vec2::from([1.0, -2.0]));
+        assert_eq!(v.wzyx(), Vec4::from([0.5, 3.0, -2.0, 1.0]));
+        assert_eq!(v.swizzle([3, 3, 0]), Vec3::from([0.5, 0.5, 1.0]));
+
+        let mut v = Vec3::from([1.0, 2.0, 3.0]);
+        v.set_z(5.0);
+        assert_eq!(v.z(), 5.0);
+        assert_eq!(v.xz(), Vec2::from([1.0, 5.0]));
+        assert_eq!(v.zyx(), Vec3::from([5.0, 2.0, 1.0]));
+        assert_eq!(v.xyz0(), Vec4::from([1.0, 2.0, 5.0, 0.0]));
+        assert_eq!(v.xyz1(), Vec4::from([1.0, 2.0, 5.0, 1.0]));
+        assert_eq!(Vec2::from([1.0, 2.0]).yx(), Vec2::from([2.0, 1.0]));
+        assert_eq!(Vec2::from([1.0, 2.0]).xy0(), Vec3::from([1.0, 2.0, 0.0]));
+    }
+
+    #[test]
+    fn vector_axis_constants() {
+        assert_eq!(Vec3::X.cross(&Vec3::Y), Vec3::Z);
+        assert_eq!(Vec2::X + Vec2::Y, VecN::from([1.0, 1.0]));
+        assert_eq!(Vec4::W, VecN::from([0.0, 0.0, 0.0, 1.0]));
+        assert_eq!(Mat3::identity() * Vec3::Z, Vec3::Z);
+        assert_eq!(Mat4::identity(), Mat::<4,4>::identity());
+    }
+
+    #[test]
+    fn vector_gram_schmidt() {
+        let vectors = [VecN::from([1.0, 1.0, 0.0]), VecN::from([1.0, 0.0, 1.0]), VecN::from([0.0, 1.0, 1.0])];
+        let basis = VecN::orthonormalize(&vectors, 1e-6).unwrap();
+        for i in 0..3 {
+            assert!((basis[i].length() - 1.0).abs() < 1e-6);
+            for j in 0..i {
+                assert!((basis[i] * basis[j]).abs() < 1e-6);
+            }
+        }
+        assert_eq!(basis[0], vectors[0].normalize());
+
+        let dependent = [VecN::from([1.0, 2.0]), VecN::from([-2.0, -4.0])];
+        assert_eq!(VecN::orthonormalize(&dependent, 1e-6), Err(MatrixError::LinearlyDependent {index: 1}));
+        assert_eq!(VecN::<3>::orthonormalize(&[], 1e-6), Ok(vec![]));
+    }
+
+    #[test]
+    fn vector_orthonormal_basis() {
+        let normals = [VecN::from([0.0, 0.0, 1.0]), VecN::from([0.0, 0.0, -1.0]), VecN::from([1.0, 2.0, 3.0]),
+                       VecN::from([-0.3, 0.9, -0.1]), VecN::from([1.0, 0.0, 0.0])];
+        for normal in normals.iter() {
+            let (t, b) = VecN::orthonormal_basis_from(normal);
+            assert!((t.length() - 1.0).abs() < 1e-6);
+            assert!((b.length() - 1.0).abs() < 1e-6);
+            assert!((t * b).abs() < 1e-6);
+            assert_eq!(t.cross(&b), normal.normalize());
+        }
+    }
+
+    #[test]
+    fn unit_vectors() {
+        let unit = Unit::try_new(VecN::from([0.0, 0.0, 5.0]), 1e-6).unwrap();
+        assert_eq!(*unit, VecN::from([0.0, 0.0, 1.0]));
+        assert_eq!(unit.length(), 1.0);
+        assert_eq!(unit.into_inner(), VecN::from([0.0, 0.0, 1.0]));
+        assert!(Unit::try_new(VecN::<3>::new(), 1e-6).is_none());
+        assert_eq!(Mat::<3,3>::rotation_unit_axis_angle(&unit, 0.5), Mat::<3,3>::rotation_z(0.5));
+        assert_eq!(Quat::from_unit_axis_angle(&unit, 0.5), Quat::from_axis_angle(&VecN::from([0.0, 0.0, 1.0]), 0.5));
+        let plane = Plane::from_point_unit_normal(&VecN::from([0.0, 0.0, 2.0]), &unit);
+        assert_eq!(plane.distance, 2.0);
+    }
+
+    #[test]
+    fn zero_axis_fallbacks() {
+        let zero = VecN::<3>::new();
+        assert_eq!(Quat::from_axis_angle(&zero, 0.5), Quat::identity());
+        assert_eq!(Mat::<3,3>::rotation_axis_angle(&zero, 0.5), Mat::<3,3>::identity());
+        let plane = Plane::from_point_normal(&VecN::from([1.0, 2.0, 3.0]), &zero);
+        assert_eq!(plane.normal, VecN::from([0.0, 0.0, 1.0]));
+        assert_eq!(plane.distance, 3.0);
+    }
+
+    #[test]
+    fn vector_relations() {
+        let x = VecN::from([2.0, 4.0, 5.0]);
+        let y = VecN::from([0.0, 3.0, 0.0]);
+        assert_eq!(x.reject(&y), VecN::from([2.0, 0.0, 5.0]));
+        assert_eq!(x.reject(&y) + x.project(&y), x);
+        assert_eq!(VecN::from([1.0, 0.0]).angle_between(&VecN::from([-3.0, 3.0])), 3.0 * std::f32::consts::FRAC_PI_4);
+        assert!((VecN::from([1.0, 1.0]).cos_similarity(&VecN::from([2.0, 2.0])) - 1.0).abs() < 1e-6);
+        assert!(VecN::from([1.0, 2.0, 3.0]).is_parallel(&VecN::from([-2.0, -4.0, -6.0]), 1e-6));
+        assert!(!x.is_parallel(&y, 1e-6));
+        assert!(VecN::from([1.0, 1.0, 0.0]).is_orthogonal(&VecN::from([1.0, -1.0, 7.0]), 1e-6));
+        assert!(!x.is_orthogonal(&y, 1e-6));
+    }
+
+    #[test]
+    fn vector_signed_angles() {
+        let quarter = std::f32::consts::FRAC_PI_2;
+        let x = VecN::from([1.0, 0.0]);
+        assert_eq!(x.signed_angle(&VecN::from([0.0, 2.0])), quarter);
+        assert_eq!(x.signed_angle(&VecN::from([0.0, -2.0])), -quarter);
+        let x = VecN::from([1.0, 0.0, 0.0]);
+        let y = VecN::from([0.0, 1.0, 0.0]);
+        assert_eq!(x.signed_angle(&y, &VecN::from([0.0, 0.0, 1.0])), quarter);
+        assert_eq!(x.signed_angle(&y, &VecN::from([0.0, 0.0, -3.0])), -quarter);
+    }
+
+    #[test]
+    fn vector_reflect_refract() {
+        let normal = VecN::from([0.0, 1.0]);
+        assert_eq!(VecN::from([1.0, -1.0]).reflect(&normal), VecN::from([1.0, 1.0]));
+
+        let incident = VecN::from([1.0, -1.0]).normalize();
+        assert_eq!(incident.refract(&normal, 1.0).unwrap(), incident);
+        let refracted = incident.refract(&normal, 1.0 / 1.5).unwrap();
+        let sin_in = incident[0];
+        let sin_out = refracted[0] / refracted.length();
+        assert!((sin_in - 1.5 * sin_out).abs() < 1e-5);
+        assert!(refracted[1] < 0.0);
+        // Going from glass to air at 45 degrees is past the critical angle
+        assert!(incident.refract(&normal, 1.5).is_none());
+    }
+
+    #[test]
+    fn vector_homogeneous() {
+        let x = VecN::from([2.0, 4.0, 5.0]);
+        assert_eq!(x.to_homogeneous(), VecN::from([2.0, 4.0, 5.0, 1.0]));
+        assert_eq!(VecN::<3>::from_homogeneous(&VecN::from([2.0, 4.0, 5.0, 2.0])), VecN::from([1.0, 2.0, 2.5]));
+        assert_eq!(VecN::<3>::from_homogeneous(&VecN::from([2.0, 4.0, 5.0, 0.0])), x);
+        assert_eq!(VecN::<2>::from_homogeneous(&VecN::from([2.0, 4.0, 4.0])), VecN::from([0.5, 1.0]));
+        assert_eq!(VecN::from([2.0, 4.0]).to_homogeneous(), VecN::from([2.0, 4.0, 1.0]));
+    }
+
     #[test]
     fn vector_length() {
         let x = VecN::from([2.0,4.0,5.0]);
@@ -239,13 +392,133 @@ mod vector_tests {
         let x = VecN::from([x1, x2]);
         assert_ne!(x, VecN::from([x1 - y1, x2 - y2]));
     }
+
+    #[test]
+    fn vec_checked_index() {
+        let mut x = VecN::from([1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(x.get(3), Some(&4.0));
+        assert_eq!(x.get(4), None);
+        *x.get_mut(0).unwrap() = 5.0;
+        assert!(x.get_mut(10).is_none());
+        assert_eq!(unsafe { *x.get_unchecked(0) }, 5.0);
+
+        assert_eq!(&x[1..3], &[2.0, 3.0]);
+        assert_eq!(&x[..2], &[5.0, 2.0]);
+        assert_eq!(&x[2..], &[3.0, 4.0]);
+        assert_eq!(&x[1..=2], x.slice(1..3));
+        assert_eq!(x[..].len(), 4);
+        x[2..].copy_from_slice(&[0.0, 0.0]);
+        assert_eq!(x, VecN::from([5.0, 2.0, 0.0, 0.0]));
+    }
     
 }
 
 #[cfg(test)]
 mod matrix_tests {
-    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::matrixs::{Mat, DepthRange, gemm};
     use my_matrix_lib::vectors::VecN;
+    use my_matrix_lib::points::Point;
+    use my_matrix_lib::errors::MatrixError;
+
+    #[test]
+    fn mat_mul_non_square() {
+        let a = Mat::from([[1.0, 2.0, 3.0],
+                            [4.0, 5.0, 6.0]]);
+        let b = Mat::from([[1.0, 0.0, 2.0, -1.0],
+                            [0.0, 1.0, 1.0, 0.0],
+                            [2.0, 1.0, 0.0, 1.0]]);
+        let product: Mat<2,4> = &a * &b;
+        assert_eq!(product, Mat::from([[7.0, 5.0, 4.0, 2.0],
+                                       [16.0, 11.0, 13.0, 2.0]]));
+
+        let mut out = Mat::from([[f32::NAN; 4]; 2]);
+        a.mul_into(&b, &mut out);
+        assert_eq!(out, product);
+    }
+
+    #[test]
+    fn mat_gemm() {
+        // Big enough to cross the block boundaries and leave a remainder of rows
+        let mut a: Mat<70, 130> = Mat::new();
+        let mut b: Mat<130, 150> = Mat::new();
+        let mut c: Mat<70, 150> = Mat::new();
+        for i in 0..70 {
+            for k in 0..130 {
+                a[i][k] = ((i * 7 + k * 3) % 11) as f32 - 5.0;
+            }
+            for j in 0..150 {
+                c[i][j] = (i + j) as f32;
+            }
+        }
+        for k in 0..130 {
+            for j in 0..150 {
+                b[k][j] = ((k * 5 + j) % 13) as f32 * 0.25;
+            }
+        }
+        let old_c = c.clone();
+        gemm(2.0, &a, &b, -1.0, &mut c);
+
+        let product = &a * &b;
+        for i in 0..70 {
+            for j in 0..150 {
+                let mut expected = 0.0;
+                for k in 0..130 {
+                    expected += a[i][k] * b[k][j];
+                }
+                assert_eq!(product[i][j], expected);
+                assert_eq!(c[i][j], 2.0 * expected - old_c[i][j]);
+            }
+        }
+    }
+
+    #[test]
+    fn mat_gemm_sum_order() {
+        // Values that round, so any change in the order of the sums shows up in the bits
+        let mut a: Mat<13, 70> = Mat::new();
+        let mut b: Mat<70, 21> = Mat::new();
+        let mut c: Mat<13, 21> = Mat::new();
+        for i in 0..13 {
+            for k in 0..70 {
+                a[i][k] = ((i * 31 + k * 17) % 29) as f32 / 7.0 - 2.0;
+            }
+            for j in 0..21 {
+                c[i][j] = (i as f32 - j as f32) / 9.0;
+            }
+        }
+        for k in 0..70 {
+            for j in 0..21 {
+                b[k][j] = ((k * 13 + j * 5) % 23) as f32 / 3.0 - 3.5;
+            }
+        }
+        let old_c = c.clone();
+        gemm(0.3, &a, &b, 1.7, &mut c);
+        for i in 0..13 {
+            for j in 0..21 {
+                let mut expected = old_c[i][j] * 1.7;
+                for k in 0..70 {
+                    expected += 0.3 * a[i][k] * b[k][j];
+                }
+                assert_eq!(c[i][j].to_bits(), expected.to_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn mat_tuple_index() {
+        let mut x = Mat::from([[2.0, 5.0],
+                              [-1.0, 0.0],
+                              [-30.3, 0.33]]);
+        assert_eq!(x[(2, 0)], -30.3);
+        x[(0, 1)] = 7.0;
+        assert_eq!(x[0][1], 7.0);
+        assert_eq!(x.get(2, 1), Some(&0.33));
+        assert_eq!(x.get(3, 0), None);
+        assert_eq!(x.get(0, 2), None);
+        *x.get_mut(1, 1).unwrap() = 4.0;
+        assert_eq!(unsafe { *x.get_unchecked(1, 1) }, 4.0);
+        unsafe { *x.get_unchecked_mut(1, 0) = 1.0; }
+        assert_eq!(x[1], VecN::from([1.0, 4.0]));
+    }
 
     #[test]
     fn mat_contains_values() {
@@ -445,7 +718,1100 @@ mod matrix_tests {
         let y = VecN::from([-33.0, 2.0]);
         assert_eq!(x * y, VecN::from([-56.0, 33.0, 181.0]));
     }
+
+    #[test]
+    fn mat_cofactors() {
+        let x = Mat::from([[2.0, 5.0, 20.0],
+                            [-1.0, -10.0, 3.0],
+                            [3.0, -2.0, 1.0]]);
+        assert_eq!(x.minor(0, 0), -4.0);
+        assert_eq!(x.minor(1, 2), -19.0);
+        assert_eq!(x.cofactor(1, 2), 19.0);
+        assert_eq!(x.cofactor_matrix(), Mat::from([[-4.0, 10.0, 32.0],
+                                                   [-45.0, -58.0, 19.0],
+                                                   [215.0, -26.0, -15.0]]));
+        assert_eq!(&x * &x.adjugate(), Mat::<3,3>::identity() * x.det());
+    }
+
+    #[test]
+    fn mat_characteristic_polynomial() {
+        let x = Mat::from([[2.0, 1.0],
+                            [1.0, 2.0]]);
+        assert_eq!(x.characteristic_polynomial(), vec![3.0, -4.0, 1.0]);
+
+        let x = Mat::from([[2.0, 5.0, 20.0],
+                            [-1.0, -10.0, 3.0],
+                            [3.0, -2.0, 1.0]]);
+        let coeffs = x.characteristic_polynomial();
+        assert_eq!(coeffs[0], -x.det());
+        assert_eq!(coeffs[2], -x.trace());
+        assert_eq!(coeffs[3], 1.0);
+    }
+
+    #[test]
+    fn mat_inverse_and_pow() {
+        let x = Mat::from([[2.0, 5.0],
+                            [-1.0, 0.0]]);
+        assert_eq!(x.inverse().unwrap(), Mat::from([[0.0, -1.0],
+                                                    [0.2, 0.4]]));
+        assert_eq!(x.pow(0).unwrap(), Mat::<2,2>::identity());
+        assert_eq!(x.pow(3).unwrap(), &(&x * &x) * &x);
+        assert_eq!(x.pow(-2).unwrap(), x.inverse().unwrap().pow(2).unwrap());
+
+        let x = Mat::from([[2.0, 5.0],
+                            [-4.0, -10.0]]);
+        assert_eq!(x.inverse().unwrap_err(), MatrixError::Singular);
+        assert!(x.pow(-1).is_err());
+
+        // Small but well conditioned
+        let x = Mat::<3,3>::identity() * 1e-7;
+        assert_eq!(x.inverse().unwrap(), Mat::<3,3>::identity() * 1e7);
+        let x = Mat::<4,4>::scaling(1e-8);
+        assert_eq!(&x * &x.inverse().unwrap(), Mat::<4,4>::identity());
+    }
+
+    #[test]
+    fn mat_exp_ln_sqrt() {
+        let theta = 1.2;
+        let x = Mat::from([[0.0, -theta],
+                            [theta, 0.0]]);
+        assert_eq!(x.exp().unwrap(), Mat::<2,2>::rotation(theta));
+
+        let x = Mat::from([[1.0, 2.0, 0.0],
+                            [0.0, 1.0, 0.0],
+                            [0.0, 0.0, 3.0]]);
+        let e = std::f32::consts::E;
+        assert_eq!(x.exp().unwrap(), Mat::from([[e, 2.0 * e, 0.0],
+                                                 [0.0, e, 0.0],
+                                                 [0.0, 0.0, e * e * e]]));
+        assert_eq!(x.exp().unwrap().ln().unwrap(), x);
+
+        let x = Mat::from([[5.0, 4.0],
+                            [4.0, 5.0]]);
+        let root = x.sqrt().unwrap();
+        assert_eq!(root, Mat::from([[2.0, 1.0],
+                                    [1.0, 2.0]]));
+        assert_eq!(Mat::<2,2>::identity().ln().unwrap(), Mat::new());
+    }
+
+    #[test]
+    fn mat_rotations_3d() {
+        let half_pi = std::f32::consts::FRAC_PI_2;
+        let x = VecN::from([1.0, 0.0, 0.0]);
+        let y = VecN::from([0.0, 1.0, 0.0]);
+        let z = VecN::from([0.0, 0.0, 1.0]);
+        assert_eq!(Mat::<3,3>::rotation_x(half_pi) * y, z);
+        assert_eq!(Mat::<3,3>::rotation_y(half_pi) * z, x);
+        assert_eq!(Mat::<3,3>::rotation_z(half_pi) * x, y);
+        assert_eq!(Mat::<3,3>::rotation_axis_angle(&VecN::from([0.0, 0.0, 5.0]), 0.7), Mat::<3,3>::rotation_z(0.7));
+        let r = Mat::<3,3>::rotation_axis_angle(&VecN::from([1.0, 1.0, 1.0]), 2.0 * std::f32::consts::FRAC_PI_3);
+        assert_eq!(r * x, y);
+
+        let m = Mat::<3,3>::translation(&VecN::from([2.0, -1.0])) * Mat::<3,3>::rotation(half_pi);
+        assert_eq!(m * VecN::from([1.0, 0.0, 1.0]), VecN::from([2.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn mat_transforms_4d() {
+        let p = VecN::from([1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(Mat::<4,4>::translation(&VecN::from([1.0, 1.0, -1.0])) * p, VecN::from([2.0, 3.0, 2.0, 1.0]));
+        assert_eq!(Mat::<4,4>::scaling(2.0) * p, VecN::from([2.0, 4.0, 6.0, 1.0]));
+        assert_eq!(Mat::<4,4>::non_uniform_scaling(&VecN::from([1.0, 0.5, -1.0])) * p, VecN::from([1.0, 1.0, -3.0, 1.0]));
+        assert_eq!(Mat::<4,4>::shear(1.0, 0.0, 0.0, 0.0, 0.0, 0.0) * p, VecN::from([3.0, 2.0, 3.0, 1.0]));
+
+        let eye = VecN::from([1.0, 2.0, 3.0]);
+        let target = VecN::from([1.0, 2.0, -7.0]);
+        let up = VecN::from([0.0, 1.0, 0.0]);
+        let view = Mat::<4,4>::look_at_rh(&eye, &target, &up);
+        assert_eq!(&view * VecN::from([1.0, 2.0, 3.0, 1.0]), VecN::from([0.0, 0.0, 0.0, 1.0]));
+        assert_eq!(view * VecN::from([1.0, 2.0, -7.0, 1.0]), VecN::from([0.0, 0.0, -10.0, 1.0]));
+        let view = Mat::<4,4>::look_at_lh(&eye, &target, &up);
+        assert_eq!(view * VecN::from([1.0, 2.0, -7.0, 1.0]), VecN::from([0.0, 0.0, 10.0, 1.0]));
+    }
+
+    #[test]
+    fn mat_projections() {
+        let depth = |m: &Mat<4,4>, z: f32| {
+            let clip = m * VecN::from([0.0, 0.0, z, 1.0]);
+            clip[2] / clip[3]
+        };
+        let proj = Mat::<4,4>::perspective(1.0, 1.5, 0.1, 100.0, DepthRange::NegativeOneToOne);
+        assert!((depth(&proj, -0.1) + 1.0).abs() < 1e-4);
+        assert!((depth(&proj, -100.0) - 1.0).abs() < 1e-4);
+        let proj = Mat::<4,4>::perspective(1.0, 1.5, 0.1, 100.0, DepthRange::ZeroToOne);
+        assert!(depth(&proj, -0.1).abs() < 1e-4);
+        assert!((depth(&proj, -100.0) - 1.0).abs() < 1e-4);
+
+        let proj = Mat::<4,4>::frustum(-1.0, 1.0, -1.0, 1.0, 1.0, 10.0, DepthRange::ZeroToOne);
+        assert!(depth(&proj, -1.0).abs() < 1e-4);
+        assert!((depth(&proj, -10.0) - 1.0).abs() < 1e-4);
+
+        let proj = Mat::<4,4>::orthographic(-2.0, 2.0, -1.0, 1.0, 1.0, 10.0, DepthRange::NegativeOneToOne);
+        assert_eq!(&proj * VecN::from([2.0, -1.0, -1.0, 1.0]), VecN::from([1.0, -1.0, -1.0, 1.0]));
+        assert_eq!(proj * VecN::from([-2.0, 1.0, -10.0, 1.0]), VecN::from([-1.0, 1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn mat_transform_points_and_vectors() {
+        let m = Mat::<4,4>::translation(&VecN::from([1.0, 2.0, 3.0])) * Mat::<4,4>::scaling(2.0);
+        let v = VecN::from([1.0, 0.0, -1.0]);
+        assert_eq!(m.transform_point(&Point::from(v)), Point::from([3.0, 2.0, 1.0]));
+        assert_eq!(m.transform_vector(&v), VecN::from([2.0, 0.0, -2.0]));
+
+        let proj = Mat::<4,4>::perspective(1.0, 1.0, 1.0, 10.0, DepthRange::ZeroToOne);
+        assert_eq!(proj.transform_point(&Point::from([0.0, 0.0, -1.0])), Point::origin());
+
+        let m = Mat::<3,3>::translation(&VecN::from([2.0, -1.0])) * Mat::<3,3>::rotation(std::f32::consts::FRAC_PI_2);
+        assert_eq!(m.transform_point(&Point::from([1.0, 0.0])), Point::from([2.0, 0.0]));
+        assert_eq!(m.transform_vector(&VecN::from([1.0, 0.0])), VecN::from([0.0, 1.0]));
+    }
+
+    #[test]
+    fn mat_normal_matrix() {
+        let m = Mat::<4,4>::non_uniform_scaling(&VecN::from([2.0, 1.0, 1.0]));
+        let normal = VecN::from([1.0, 1.0, 0.0]);
+        let tangent = VecN::from([1.0, -1.0, 0.0]);
+        let new_normal = m.normal_matrix().unwrap() * normal;
+        assert_eq!(new_normal * m.transform_vector(&tangent), 0.0);
+        assert!(Mat::<4,4>::scaling(0.0).normal_matrix().is_err());
+    }
+
+    #[test]
+    fn mat_orthonormalize() {
+        let rot = Mat::<3,3>::rotation_axis_angle(&VecN::from([1.0, 2.0, -1.0]), 0.8);
+        let drifted = Mat::from([rot[0] + VecN::from([0.01, -0.02, 0.0]), rot[1] * 1.03, rot[2] - VecN::from([0.0, 0.01, 0.02])]);
+        let fixed = drifted.orthonormalize();
+        assert_eq!(&fixed * &fixed.transpose(), Mat::<3,3>::identity());
+        assert!((fixed.det() - 1.0).abs() < 1e-5);
+        assert_eq!(rot.orthonormalize(), rot);
+    }
+}
+
+
+
+#[cfg(test)]
+mod quaternion_tests {
+    use my_matrix_lib::quaternions::Quat;
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+    use my_matrix_lib::errors::MatrixError;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn quat_product_and_inverse() {
+        let i = Quat::new(1.0, 0.0, 0.0, 0.0);
+        let j = Quat::new(0.0, 1.0, 0.0, 0.0);
+        let k = Quat::new(0.0, 0.0, 1.0, 0.0);
+        assert_eq!(i * j, k);
+        assert_eq!(j * i, Quat::new(0.0, 0.0, -1.0, 0.0));
+        assert_eq!(i * i, Quat::new(0.0, 0.0, 0.0, -1.0));
+
+        let q = Quat::new(1.0, 2.0, -3.0, 4.0);
+        assert_eq!(q * q.inverse().unwrap(), Quat::identity());
+        assert_eq!(q.conjugate(), Quat::new(-1.0, -2.0, 3.0, 4.0));
+        assert_eq!(Quat::new(0.0, 0.0, 0.0, 0.0).inverse().unwrap_err(), MatrixError::Singular);
+    }
+
+    #[test]
+    fn quat_rotation() {
+        let q = Quat::from_axis_angle(&VecN::from([0.0, 0.0, 1.0]), FRAC_PI_2);
+        assert_eq!(q.rotate(&VecN::from([1.0, 0.0, 0.0])), VecN::from([0.0, 1.0, 0.0]));
+        assert_eq!(Mat::<3,3>::from(&q), Mat::<3,3>::rotation_z(FRAC_PI_2));
+
+        let from = VecN::from([1.0, 2.0, 3.0]);
+        let to = VecN::from([-2.0, 0.5, 1.0]);
+        let q = Quat::from_two_vectors(&from, &to);
+        assert_eq!(q.rotate(&from).normalize(), to.normalize());
+        let q = Quat::from_two_vectors(&from, &(from * -1.0));
+        assert_eq!(q.rotate(&from), from * -1.0);
+
+        let (axis, angle) = Quat::from_axis_angle(&VecN::from([0.0, 3.0, 0.0]), 1.0).axis_angle();
+        assert_eq!(axis, VecN::from([0.0, 1.0, 0.0]));
+        assert!((angle - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn quat_matrix_round_trip() {
+        let mats = [Mat::<3,3>::rotation_x(PI),
+                    Mat::<3,3>::rotation_y(2.5),
+                    Mat::<3,3>::rotation_axis_angle(&VecN::from([1.0, -2.0, 0.5]), 3.0),
+                    Mat::<3,3>::identity()];
+        for mat in mats.iter() {
+            assert_eq!(Mat::<3,3>::from(&Quat::from(mat)), *mat);
+        }
+        let mat = Mat::<4,4>::from(&Quat::from_axis_angle(&VecN::from([1.0, 1.0, 0.0]), 0.3));
+        assert_eq!(mat[3], VecN::from([0.0, 0.0, 0.0, 1.0]));
+        assert_eq!(Mat::<4,4>::from(&Quat::from(&mat)), mat);
+    }
+
+    #[test]
+    fn quat_interpolation() {
+        let axis = VecN::from([0.0, 0.0, 1.0]);
+        let a = Quat::from_axis_angle(&axis, 0.0);
+        let b = Quat::from_axis_angle(&axis, FRAC_PI_2);
+        assert_eq!(a.slerp(&b, 0.5), Quat::from_axis_angle(&axis, FRAC_PI_2 / 2.0));
+        assert_eq!(a.slerp(&b, 0.0), a);
+        assert_eq!(a.slerp(&b, 1.0), b);
+        assert_eq!(a.nlerp(&b, 0.5), Quat::from_axis_angle(&axis, FRAC_PI_2 / 2.0));
+        // Negated quaternions are the same rotation so the short path is taken
+        let neg_b = Quat::from(b.vals * -1.0);
+        assert_eq!(a.slerp(&neg_b, 0.5), Quat::from_axis_angle(&axis, FRAC_PI_2 / 2.0));
+    }
+}
+
+#[cfg(test)]
+mod euler_angle_tests {
+    use my_matrix_lib::euler_angles::{EulerAngles, EulerOrder, EulerFrame};
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    const ORDERS: [EulerOrder; 12] = [EulerOrder::XYZ, EulerOrder::XZY, EulerOrder::YXZ, EulerOrder::YZX,
+                                      EulerOrder::ZXY, EulerOrder::ZYX, EulerOrder::XYX, EulerOrder::XZX,
+                                      EulerOrder::YXY, EulerOrder::YZY, EulerOrder::ZXZ, EulerOrder::ZYZ];
+
+    #[test]
+    fn euler_to_mat() {
+        let angles = EulerAngles::new(0.3, -0.7, 1.1, EulerOrder::XYZ, EulerFrame::Intrinsic);
+        assert_eq!(angles.to_mat(), Mat::<3,3>::rotation_x(0.3) * Mat::<3,3>::rotation_y(-0.7) * Mat::<3,3>::rotation_z(1.1));
+        let angles = EulerAngles::new(0.3, -0.7, 1.1, EulerOrder::ZXZ, EulerFrame::Extrinsic);
+        assert_eq!(angles.to_mat(), Mat::<3,3>::rotation_z(1.1) * Mat::<3,3>::rotation_x(-0.7) * Mat::<3,3>::rotation_z(0.3));
+    }
+
+    #[test]
+    fn euler_round_trip() {
+        let mat = Mat::<3,3>::rotation_axis_angle(&VecN::from([0.4, -1.0, 2.0]), 1.3);
+        for order in ORDERS.iter() {
+            for frame in [EulerFrame::Intrinsic, EulerFrame::Extrinsic].iter() {
+                let angles = EulerAngles::from_mat(&mat, *order, *frame);
+                assert_eq!(angles.to_mat(), mat, "{:?} {:?}", order, frame);
+            }
+        }
+
+        let angles = EulerAngles::new(0.3, -0.7, 1.1, EulerOrder::YZX, EulerFrame::Intrinsic);
+        assert_eq!(EulerAngles::from_mat(&angles.to_mat(), EulerOrder::YZX, EulerFrame::Intrinsic), angles);
+        let converted = angles.convert(EulerOrder::ZYZ, EulerFrame::Extrinsic);
+        assert_eq!(converted.to_mat(), angles.to_mat());
+    }
+
+    #[test]
+    fn euler_gimbal_lock() {
+        for order in ORDERS.iter() {
+            let locked = if order.is_proper() { [0.0, PI] } else { [FRAC_PI_2, -FRAC_PI_2] };
+            for &middle in locked.iter() {
+                let mat = EulerAngles::new(0.4, middle, 0.9, *order, EulerFrame::Intrinsic).to_mat();
+                let angles = EulerAngles::from_mat(&mat, *order, EulerFrame::Intrinsic);
+                assert_eq!(angles.angles[2], 0.0);
+                assert_eq!(angles.to_mat(), mat, "{:?}", order);
+            }
+        }
+    }
+
+    #[test]
+    fn euler_gimbal_lock_extrinsic() {
+        for order in ORDERS.iter() {
+            let locked = if order.is_proper() { [0.0, PI] } else { [FRAC_PI_2, -FRAC_PI_2] };
+            for &middle in locked.iter() {
+                let mat = EulerAngles::new(0.4, middle, 0.9, *order, EulerFrame::Extrinsic).to_mat();
+                let angles = EulerAngles::from_mat(&mat, *order, EulerFrame::Extrinsic);
+                assert_eq!(angles.angles[2], 0.0);
+                assert_eq!(angles.to_mat(), mat, "{:?}", order);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod affine_tests {
+    use my_matrix_lib::affines::{Affine2, Affine3, Isometry3};
+    use my_matrix_lib::quaternions::Quat;
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+    use my_matrix_lib::points::Point;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn affine2_transforms() {
+        let a = Affine2::new(Mat::<2,2>::rotation(FRAC_PI_2), VecN::from([2.0, -1.0]));
+        assert_eq!(a.transform_point(&Point::from([1.0, 0.0])), Point::from([2.0, 0.0]));
+        assert_eq!(a.transform_vector(&VecN::from([1.0, 0.0])), VecN::from([0.0, 1.0]));
+        assert_eq!(Mat::<3,3>::from(&a), Mat::<3,3>::translation(&VecN::from([2.0, -1.0])) * Mat::<3,3>::rotation(FRAC_PI_2));
+        assert_eq!(Affine2::from(&Mat::<3,3>::from(&a)), a);
+        assert_eq!(&a * &a.inverse().unwrap(), Affine2::identity());
+    }
+
+    #[test]
+    fn affine3_composition() {
+        let a = Affine3::new(Mat::<3,3>::rotation_x(0.4), VecN::from([1.0, 2.0, 3.0]));
+        let b = Affine3::new(Mat::from([[2.0, 0.0, 1.0],
+                                        [0.0, 1.0, 0.0],
+                                        [0.0, 0.0, 0.5]]), VecN::from([-1.0, 0.0, 4.0]));
+        let p = Point::from([0.5, -2.0, 1.0]);
+        assert_eq!((&a * &b).transform_point(&p), a.transform_point(&b.transform_point(&p)));
+        assert_eq!(Mat::<4,4>::from(&(&a * &b)), Mat::<4,4>::from(&a) * Mat::<4,4>::from(&b));
+        assert_eq!(Affine3::from(&Mat::<4,4>::from(&b)), b);
+        assert_eq!(b.inverse().unwrap().transform_point(&b.transform_point(&p)), p);
+        assert!(Affine3::from_linear(Mat::new()).inverse().is_err());
+    }
+
+    #[test]
+    fn isometry3_transforms() {
+        let iso = Isometry3::new(Quat::from_axis_angle(&VecN::from([0.0, 0.0, 1.0]), FRAC_PI_2), VecN::from([1.0, 0.0, 0.0]));
+        assert_eq!(iso.transform_point(&Point::from([1.0, 0.0, 0.0])), Point::from([1.0, 1.0, 0.0]));
+        assert_eq!(iso.transform_vector(&VecN::from([1.0, 0.0, 0.0])), VecN::from([0.0, 1.0, 0.0]));
+
+        let other = Isometry3::new(Quat::from_axis_angle(&VecN::from([1.0, 2.0, 0.0]), 0.3), VecN::from([0.0, -3.0, 2.0]));
+        let p = Point::from([0.5, -2.0, 1.0]);
+        assert_eq!((iso * other).transform_point(&p), iso.transform_point(&other.transform_point(&p)));
+        assert_eq!(iso.inverse().transform_point(&iso.transform_point(&p)), p);
+        assert_eq!(Mat::<4,4>::from(&iso).inverse().unwrap(), Mat::<4,4>::from(&iso.inverse()));
+        assert_eq!(Affine3::from(&iso).transform_point(&p), iso.transform_point(&p));
+    }
+}
+
+#[cfg(test)]
+mod point_tests {
+    use my_matrix_lib::points::Point;
+    use my_matrix_lib::vectors::VecN;
+    use my_matrix_lib::errors::MatrixError;
+
+    #[test]
+    fn point_arithmetic() {
+        let a = Point::from([1.0, 2.0, 3.0]);
+        let b = Point::from([4.0, 6.0, 3.0]);
+        assert_eq!(b - a, VecN::from([3.0, 4.0, 0.0]));
+        assert_eq!(a.vec_to(&b), VecN::from([3.0, 4.0, 0.0]));
+        assert_eq!(a.dist(&b), 5.0);
+        assert_eq!(a + VecN::from([3.0, 4.0, 0.0]), b);
+        assert_eq!(b - VecN::from([3.0, 4.0, 0.0]), a);
+        assert_eq!(VecN::from(a), VecN::from([1.0, 2.0, 3.0]));
+        assert_eq!(Point::from(VecN::from([1.0, 2.0, 3.0])), a);
+        assert_eq!(Point::<3>::origin().to_vec(), VecN::new());
+        assert_eq!(b[1], 6.0);
+    }
+
+    #[test]
+    fn point_combinations() {
+        let points = [Point::from([0.0, 0.0]), Point::from([4.0, 0.0]), Point::from([2.0, 3.0])];
+        assert_eq!(Point::centroid(&points).unwrap(), Point::from([2.0, 1.0]));
+        assert_eq!(Point::<2>::centroid(&[]), None);
+        assert_eq!(Point::affine_combination(&points, &[0.5, 0.5, 0.0]).unwrap(), Point::from([2.0, 0.0]));
+        assert_eq!(Point::affine_combination(&points, &[0.25, 0.25, 0.5]).unwrap(), Point::from([2.0, 1.5]));
+        assert_eq!(Point::affine_combination(&points, &[1.0, 1.0, 1.0]), Err(MatrixError::WeightSum {sum: 3.0}));
+        assert_eq!(Point::affine_combination(&points, &[1.0]), Err(MatrixError::DimensionMismatch {expected: 3, found: 1}));
+        assert_eq!(Point::<2>::affine_combination(&[], &[]), Err(MatrixError::TooFewPoints {needed: 1, found: 0}));
+    }
+}
+
+#[cfg(test)]
+mod geometry_tests {
+    use my_matrix_lib::geometry::{Ray, Plane, Sphere, Aabb, Triangle, LineSegment};
+    use my_matrix_lib::vectors::VecN;
+
+    #[test]
+    fn ray_intersections() {
+        let ray = Ray::new(VecN::from([0.0, 0.0, -5.0]), VecN::from([0.0, 0.0, 1.0]));
+        let plane = Plane::from_point_normal(&VecN::from([0.0, 0.0, 2.0]), &VecN::from([0.0, 0.0, -3.0]));
+        assert_eq!(ray.intersect_plane(&plane), Some(7.0));
+        let behind = Plane::from_point_normal(&VecN::from([0.0, 0.0, -6.0]), &VecN::from([0.0, 0.0, 1.0]));
+        assert_eq!(ray.intersect_plane(&behind), None);
+
+        let sphere = Sphere::new(VecN::from([0.0, 0.0, 0.0]), 2.0);
+        assert_eq!(ray.intersect_sphere(&sphere), Some(3.0));
+        assert_eq!(Ray::new(VecN::new(), VecN::from([0.0, 0.0, 1.0])).intersect_sphere(&sphere), Some(2.0));
+        assert_eq!(Ray::new(VecN::from([3.0, 0.0, -5.0]), VecN::from([0.0, 0.0, 1.0])).intersect_sphere(&sphere), None);
+
+        let aabb = Aabb::new(VecN::from([1.0, 1.0, 1.0]), VecN::from([-1.0, -1.0, -1.0]));
+        assert_eq!(ray.intersect_aabb(&aabb), Some(4.0));
+        assert_eq!(Ray::new(VecN::from([2.0, 0.0, -5.0]), VecN::from([0.0, 0.0, 1.0])).intersect_aabb(&aabb), None);
+        assert_eq!(Ray::new(VecN::new(), VecN::from([1.0, 1.0, 0.0])).intersect_aabb(&aabb), Some(0.0));
+        // Parallel to the x slab and sitting exactly on its faces
+        assert_eq!(Ray::new(VecN::from([1.0, 0.0, -5.0]), VecN::from([0.0, 0.0, 1.0])).intersect_aabb(&aabb), Some(4.0));
+        assert_eq!(Ray::new(VecN::from([-1.0, 1.0, -5.0]), VecN::from([0.0, 0.0, 1.0])).intersect_aabb(&aabb), Some(4.0));
+        assert_eq!(Ray::new(VecN::from([1.5, 0.0, -5.0]), VecN::from([0.0, 0.0, 1.0])).intersect_aabb(&aabb), None);
+
+        let triangle = Triangle::new(VecN::from([-1.0, -1.0, 1.0]), VecN::from([1.0, -1.0, 1.0]), VecN::from([0.0, 1.0, 1.0]));
+        assert_eq!(ray.intersect_triangle(&triangle), Some(6.0));
+        assert_eq!(Ray::new(VecN::from([0.9, 0.9, -5.0]), VecN::from([0.0, 0.0, 1.0])).intersect_triangle(&triangle), None);
+    }
+
+    #[test]
+    fn closest_points_and_distances() {
+        let plane = Plane::from_points(&VecN::new(), &VecN::from([1.0, 0.0, 0.0]), &VecN::from([0.0, 1.0, 0.0])).unwrap();
+        assert_eq!(plane.normal, VecN::from([0.0, 0.0, 1.0]));
+        assert_eq!(plane.signed_distance(&VecN::from([4.0, 2.0, -3.0])), -3.0);
+        assert_eq!(plane.closest_point(&VecN::from([4.0, 2.0, -3.0])), VecN::from([4.0, 2.0, 0.0]));
+        assert!(Plane::from_points(&VecN::new(), &VecN::from([1.0, 0.0, 0.0]), &VecN::from([2.0, 0.0, 0.0])).is_none());
+
+        let sphere = Sphere::new(VecN::from([1.0, 0.0, 0.0]), 1.0);
+        assert_eq!(sphere.closest_point(&VecN::from([5.0, 0.0, 0.0])), VecN::from([2.0, 0.0, 0.0]));
+        assert_eq!(sphere.distance(&VecN::from([5.0, 0.0, 0.0])), 3.0);
+
+        let aabb = Aabb::from_points(&[VecN::new(), VecN::from([2.0, 2.0, 2.0]), VecN::from([1.0, -1.0, 0.5])]).unwrap();
+        assert_eq!(aabb.min, VecN::from([0.0, -1.0, 0.0]));
+        assert_eq!(aabb.closest_point(&VecN::from([3.0, 1.0, -2.0])), VecN::from([2.0, 1.0, 0.0]));
+        assert_eq!(aabb.distance(&VecN::from([1.0, 1.0, 1.0])), 0.0);
+
+        let triangle = Triangle::new(VecN::new(), VecN::from([2.0, 0.0, 0.0]), VecN::from([0.0, 2.0, 0.0]));
+        assert_eq!(triangle.closest_point(&VecN::from([0.5, 0.5, 3.0])), VecN::from([0.5, 0.5, 0.0]));
+        assert_eq!(triangle.closest_point(&VecN::from([-1.0, -1.0, 0.0])), VecN::new());
+        assert_eq!(triangle.closest_point(&VecN::from([2.0, 2.0, 0.0])), VecN::from([1.0, 1.0, 0.0]));
+        assert_eq!(triangle.closest_point(&VecN::from([1.0, -3.0, 1.0])), VecN::from([1.0, 0.0, 0.0]));
+        assert_eq!(triangle.area(), 2.0);
+
+        let segment = LineSegment::new(VecN::new(), VecN::from([0.0, 0.0, 4.0]));
+        assert_eq!(segment.closest_point(&VecN::from([1.0, 0.0, 2.0])), VecN::from([0.0, 0.0, 2.0]));
+        assert_eq!(segment.distance(&VecN::from([0.0, 3.0, 8.0])), 5.0);
+    }
+
+    #[test]
+    fn overlap_tests() {
+        let a = Aabb::new(VecN::new(), VecN::from([1.0, 1.0, 1.0]));
+        let b = Aabb::new(VecN::from([0.5, 0.5, 0.5]), VecN::from([2.0, 2.0, 2.0]));
+        let c = Aabb::new(VecN::from([1.5, 0.0, 0.0]), VecN::from([2.0, 1.0, 1.0]));
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+        assert!(Sphere::new(VecN::from([2.0, 0.5, 0.5]), 1.1).intersects_aabb(&a));
+        assert!(!Sphere::new(VecN::from([2.0, 2.0, 2.0]), 1.1).intersects_aabb(&a));
+    }
 }
 
+#[cfg(test)]
+mod polygon_tests {
+    use my_matrix_lib::polygons::*;
+    use my_matrix_lib::vectors::VecN;
+
+    fn square() -> Vec<VecN<2>> {
+        vec![VecN::from([0.0, 0.0]), VecN::from([2.0, 0.0]), VecN::from([2.0, 2.0]), VecN::from([0.0, 2.0])]
+    }
 
+    #[test]
+    fn orientation() {
+        let a = VecN::from([0.0, 0.0]);
+        let b = VecN::from([1.0, 0.0]);
+        assert!(orient2d(&a, &b, &VecN::from([0.5, 1.0])) > 0.0);
+        assert!(orient2d(&a, &b, &VecN::from([0.5, -1.0])) < 0.0);
+        assert_eq!(orient2d(&a, &b, &VecN::from([3.0, 0.0])), 0.0);
+        assert_eq!(b.perp_dot(&VecN::from([0.0, 2.0])), 2.0);
+
+        // Twice the area is 1e-60, which is 0 as an f32
+        let tiny = [VecN::from([0.0, 0.0]), VecN::from([1e-30, 0.0]), VecN::from([0.0, 1e-30])];
+        assert!(orient2d(&tiny[0], &tiny[1], &tiny[2]) > 0.0);
+        assert_eq!(convex_hull(&tiny).len(), 3);
+        let crossing = segment_intersection(&tiny[0], &VecN::from([1e-30, 1e-30]), &tiny[1], &tiny[2]);
+        assert_eq!(crossing, SegmentIntersection::Point(VecN::from([5e-31, 5e-31])));
+    }
+
+    #[test]
+    fn segments() {
+        let (a1, a2) = (VecN::from([0.0, 0.0]), VecN::from([2.0, 2.0]));
+        assert_eq!(segment_intersection(&a1, &a2, &VecN::from([0.0, 2.0]), &VecN::from([2.0, 0.0])),
+                   SegmentIntersection::Point(VecN::from([1.0, 1.0])));
+        assert_eq!(segment_intersection(&a1, &a2, &VecN::from([3.0, 0.0]), &VecN::from([3.0, 5.0])),
+                   SegmentIntersection::None);
+        assert_eq!(segment_intersection(&a1, &a2, &VecN::from([3.0, 3.0]), &VecN::from([1.0, 1.0])),
+                   SegmentIntersection::Overlap(VecN::from([1.0, 1.0]), VecN::from([2.0, 2.0])));
+        assert_eq!(segment_intersection(&a1, &a2, &VecN::from([2.0, 2.0]), &VecN::from([4.0, 4.0])),
+                   SegmentIntersection::Point(VecN::from([2.0, 2.0])));
+        assert_eq!(segment_intersection(&a1, &a2, &VecN::from([0.0, 1.0]), &VecN::from([1.0, 2.0])),
+                   SegmentIntersection::None);
+
+        // Either segment collapsed to a single point
+        let a = (VecN::from([0.0, 0.0]), VecN::from([2.0, 0.0]));
+        let off = VecN::from([1.0, 5.0]);
+        let on = VecN::from([1.0, 0.0]);
+        assert_eq!(segment_intersection(&a.0, &a.1, &off, &off), SegmentIntersection::None);
+        assert_eq!(segment_intersection(&a.0, &a.1, &on, &on), SegmentIntersection::Point(on));
+        assert_eq!(segment_intersection(&off, &off, &a.0, &a.1), SegmentIntersection::None);
+        assert_eq!(segment_intersection(&on, &on, &a.0, &a.1), SegmentIntersection::Point(on));
+    }
+
+    #[test]
+    fn polygon_properties() {
+        let mut poly = square();
+        assert_eq!(signed_area(&poly), 4.0);
+        assert_eq!(polygon_centroid(&poly), Some(VecN::from([1.0, 1.0])));
+        assert!(point_in_polygon(&VecN::from([1.0, 1.5]), &poly));
+        assert!(!point_in_polygon(&VecN::from([3.0, 1.0]), &poly));
+        poly.reverse();
+        assert_eq!(signed_area(&poly), -4.0);
+        assert_eq!(polygon_centroid(&poly), Some(VecN::from([1.0, 1.0])));
+
+        let l_shape = vec![VecN::from([0.0, 0.0]), VecN::from([2.0, 0.0]), VecN::from([2.0, 1.0]),
+                           VecN::from([1.0, 1.0]), VecN::from([1.0, 2.0]), VecN::from([0.0, 2.0])];
+        assert_eq!(signed_area(&l_shape), 3.0);
+        assert_eq!(polygon_centroid(&l_shape), Some(VecN::from([5.0 / 6.0, 5.0 / 6.0])));
+        assert!(!point_in_polygon(&VecN::from([1.5, 1.5]), &l_shape));
+    }
+
+    #[test]
+    fn hull() {
+        let mut points = square();
+        points.push(VecN::from([1.0, 1.0]));
+        points.push(VecN::from([1.0, 0.0]));
+        points.push(VecN::from([2.0, 2.0]));
+        assert_eq!(convex_hull(&points), vec![VecN::from([0.0, 0.0]), VecN::from([2.0, 0.0]),
+                                              VecN::from([2.0, 2.0]), VecN::from([0.0, 2.0])]);
+    }
+
+    #[test]
+    fn ear_clipping() {
+        let l_shape = vec![VecN::from([0.0, 0.0]), VecN::from([2.0, 0.0]), VecN::from([2.0, 1.0]),
+                           VecN::from([1.0, 1.0]), VecN::from([1.0, 2.0]), VecN::from([0.0, 2.0])];
+        for poly in [l_shape.clone(), l_shape.iter().rev().cloned().collect()].iter() {
+            let triangles = triangulate(poly).unwrap();
+            assert_eq!(triangles.len(), 4);
+            let mut area = 0.0;
+            for t in triangles.iter() {
+                let tri = [poly[t[0]], poly[t[1]], poly[t[2]]];
+                assert!(signed_area(&tri) > 0.0);
+                area += signed_area(&tri);
+            }
+            assert_eq!(area, 3.0);
+        }
+        assert_eq!(triangulate(&square()[0..2]), None);
+
+        // Areas around 1e-60 only have a sign in f64
+        let tiny: Vec<VecN<2>> = l_shape.iter().map(|p| p * 1e-30).collect();
+        assert_eq!(triangulate(&tiny).unwrap(), triangulate(&l_shape).unwrap());
+        let tiny_cw: Vec<VecN<2>> = tiny.iter().rev().cloned().collect();
+        let l_shape_cw: Vec<VecN<2>> = l_shape.iter().rev().cloned().collect();
+        assert_eq!(triangulate(&tiny_cw).unwrap(), triangulate(&l_shape_cw).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod curve_tests {
+    use my_matrix_lib::curves::*;
+    use my_matrix_lib::vectors::VecN;
+    use my_matrix_lib::errors::MatrixError;
 
+    #[test]
+    fn vector_interpolation() {
+        let a = VecN::from([1.0, 0.0]);
+        let b = VecN::from([0.0, 1.0]);
+        assert_eq!(a.lerp(&b, 0.25), VecN::from([0.75, 0.25]));
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert_eq!(a.slerp(&b, 0.5), VecN::from([half, half]));
+        assert_eq!(a.slerp(&a, 0.5), a);
+
+        // Opposite vectors turn through a perpendicular instead of collapsing to zero
+        let x = VecN::from([1.0, 0.0, 0.0]);
+        let neg_x = VecN::from([-1.0, 0.0, 0.0]);
+        let halfway = x.slerp(&neg_x, 0.5);
+        assert!((halfway.length() - 1.0).abs() < 1e-6);
+        assert!((halfway * x).abs() < 1e-6);
+        assert_eq!(x.slerp(&neg_x, 0.0), x);
+        assert_eq!(x.slerp(&neg_x, 1.0), neg_x);
+        assert_eq!(VecN::from([1.0]).slerp(&VecN::from([-1.0]), 0.25), VecN::from([0.5]));
+        assert_eq!(a.smoothstep(&b, 0.5), VecN::from([0.5, 0.5]));
+        assert_eq!(a.smoothstep(&b, 0.25), a.lerp(&b, 0.15625));
+        assert_eq!(a.smoothstep(&b, 2.0), b);
+        assert_eq!(a.smootherstep(&b, 0.5), VecN::from([0.5, 0.5]));
+    }
+
+    #[test]
+    fn bezier_curves() {
+        let quad = QuadraticBezier::new(VecN::from([0.0, 0.0]), VecN::from([1.0, 2.0]), VecN::from([2.0, 0.0]));
+        assert_eq!(quad.eval(0.5), VecN::from([1.0, 1.0]));
+        assert_eq!(quad.derivative(0.0), VecN::from([2.0, 4.0]));
+
+        let cubic = CubicBezier::new(VecN::from([0.0, 0.0, 0.0]), VecN::from([0.0, 1.0, 0.0]),
+                                     VecN::from([1.0, 1.0, 0.0]), VecN::from([1.0, 0.0, 1.0]));
+        assert_eq!(cubic.eval(0.0), cubic.p0);
+        assert_eq!(cubic.eval(1.0), cubic.p3);
+        assert_eq!(cubic.derivative(1.0), (cubic.p3 - cubic.p2) * 3.0);
+        let (left, right) = cubic.split(0.3);
+        assert_eq!(left.eval(1.0), cubic.eval(0.3));
+        assert_eq!(right.eval(0.5), cubic.eval(0.65));
+
+        // Numeric check of the derivative
+        let h = 1e-3;
+        let numeric = (cubic.eval(0.4 + h) - cubic.eval(0.4 - h)) / (2.0 * h);
+        assert!(numeric.dist(&cubic.derivative(0.4)) < 1e-2);
+    }
+
+    #[test]
+    fn hermite_and_catmull_rom() {
+        let hermite = Hermite::new(VecN::from([0.0, 0.0]), VecN::from([1.0, 0.0]), VecN::from([1.0, 1.0]), VecN::from([0.0, 1.0]));
+        assert_eq!(hermite.eval(0.0), hermite.p0);
+        assert_eq!(hermite.eval(1.0), hermite.p1);
+        assert_eq!(hermite.derivative(0.0), hermite.m0);
+        assert_eq!(hermite.derivative(1.0), hermite.m1);
+
+        let points = vec![VecN::from([0.0, 0.0]), VecN::from([1.0, 0.0]), VecN::from([2.0, 1.0]),
+                          VecN::from([3.0, 1.0]), VecN::from([4.0, 0.0])];
+        let spline = CatmullRom::new(points.clone()).unwrap();
+        assert_eq!(spline.domain(), (0.0, 2.0));
+        assert_eq!(spline.eval(0.0), points[1]);
+        assert_eq!(spline.eval(1.0), points[2]);
+        assert_eq!(spline.eval(2.0), points[3]);
+        assert_eq!(spline.derivative(1.0), (points[3] - points[1]) * 0.5);
+        assert_eq!(CatmullRom::new(points[0..3].to_vec()), Err(MatrixError::TooFewPoints {needed: 4, found: 3}));
+    }
+
+    #[test]
+    fn b_splines() {
+        let points = vec![VecN::from([0.0, 0.0]), VecN::from([1.0, 2.0]), VecN::from([3.0, 2.0]), VecN::from([4.0, 0.0])];
+        // A clamped cubic with 4 points is the same as a bezier
+        let spline = BSpline::clamped(3, points.clone()).unwrap();
+        let bezier = CubicBezier::new(points[0], points[1], points[2], points[3]);
+        for &t in [0.0, 0.2, 0.5, 0.9, 1.0].iter() {
+            assert_eq!(spline.eval(t), bezier.eval(t));
+            assert_eq!(spline.derivative(t), bezier.derivative(t));
+        }
+
+        let spline = BSpline::uniform(2, points.clone()).unwrap();
+        assert_eq!(spline.domain(), (2.0, 4.0));
+        // Uniform quadratic starts at the midpoint of the first two points
+        assert_eq!(spline.eval(2.0), points[0].lerp(&points[1], 0.5));
+        assert_eq!(spline.eval(4.0), points[2].lerp(&points[3], 0.5));
+
+        let spline = BSpline::new(1, points.clone(), vec![0.0, 0.0, 1.0, 3.0, 4.0, 4.0]).unwrap();
+        assert_eq!(spline.eval(2.0), points[1].lerp(&points[2], 0.5));
+        assert_eq!(BSpline::new(1, points.clone(), vec![0.0, 1.0]), Err(MatrixError::DimensionMismatch {expected: 6, found: 2}));
+        assert_eq!(BSpline::new(1, points.clone(), vec![0.0, 0.0, 2.0, 1.0, 4.0, 4.0]), Err(MatrixError::DecreasingKnots {index: 3}));
+        assert_eq!(BSpline::clamped(4, points), Err(MatrixError::TooFewPoints {needed: 5, found: 4}));
+    }
+
+    #[test]
+    fn arc_length_and_flatten() {
+        let line = CubicBezier::new(VecN::from([0.0, 0.0]), VecN::from([0.1, 0.0]), VecN::from([0.2, 0.0]), VecN::from([3.0, 0.0]));
+        let table = line.arc_length_table(1000);
+        assert!((table.length() - 3.0).abs() < 1e-4);
+        assert!(line.eval_at_length(&table, 1.5).dist(&VecN::from([1.5, 0.0])) < 1e-3);
+        assert_eq!(line.eval_at_length(&table, 10.0), VecN::from([3.0, 0.0]));
+
+        let arc = QuadraticBezier::new(VecN::from([0.0, 0.0]), VecN::from([1.0, 2.0]), VecN::from([2.0, 0.0]));
+        let coarse = arc.flatten(0.1);
+        let fine = arc.flatten(0.001);
+        assert!(fine.len() > coarse.len());
+        assert_eq!(fine[0], arc.p0);
+        assert_eq!(*fine.last().unwrap(), arc.p2);
+        assert!((arc.arc_length(2000) - 2.9578857).abs() < 1e-3);
+    }
+
+    #[test]
+    fn arc_length_matches_table() {
+        // arc_length skips the table so it works without alloc, it should still give the same bits
+        let arc = QuadraticBezier::new(VecN::from([0.0, 0.0]), VecN::from([1.0, 2.0]), VecN::from([2.0, 0.0]));
+        for samples in [0, 1, 7, 500] {
+            assert_eq!(arc.arc_length(samples), arc.arc_length_table(samples).length());
+        }
+    }
+}
+
+#[cfg(test)]
+mod coordinate_tests {
+    use my_matrix_lib::coordinates::{AngleConvention, UpAxis, SphericalConvention};
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    const CONVENTIONS: [SphericalConvention; 4] = [
+        SphericalConvention {angles: AngleConvention::Physics, up: UpAxis::Z},
+        SphericalConvention {angles: AngleConvention::Physics, up: UpAxis::Y},
+        SphericalConvention {angles: AngleConvention::Math, up: UpAxis::Z},
+        SphericalConvention {angles: AngleConvention::Math, up: UpAxis::Y},
+    ];
+
+    #[test]
+    fn polar() {
+        let v = VecN::from([-1.0, 1.0]);
+        let polar = v.to_polar();
+        assert_eq!(polar, VecN::from([2f32.sqrt(), 3.0 * FRAC_PI_4]));
+        assert_eq!(VecN::<2>::from_polar(&polar), v);
+        assert_eq!(VecN::<2>::polar_jacobian(&VecN::from([2.0, 0.0])), Mat::from([[1.0, 0.0],
+                                                                                  [0.0, 2.0]]));
+    }
+
+    #[test]
+    fn spherical_values() {
+        let physics_z = SphericalConvention::new(AngleConvention::Physics, UpAxis::Z);
+        assert_eq!(VecN::from([0.0, 2.0, 0.0]).to_spherical(physics_z), VecN::from([2.0, FRAC_PI_2, FRAC_PI_2]));
+        let math_z = SphericalConvention::new(AngleConvention::Math, UpAxis::Z);
+        assert_eq!(VecN::from([0.0, 0.0, -3.0]).to_spherical(math_z), VecN::from([3.0, 0.0, std::f32::consts::PI]));
+        let physics_y = SphericalConvention::new(AngleConvention::Physics, UpAxis::Y);
+        assert_eq!(VecN::from([0.0, 2.0, 0.0]).to_spherical(physics_y), VecN::from([2.0, 0.0, 0.0]));
+        assert_eq!(VecN::from([1.0, 0.0, 0.0]).to_spherical(physics_y), VecN::from([1.0, FRAC_PI_2, FRAC_PI_2]));
+        assert_eq!(VecN::<3>::new().to_spherical(physics_y), VecN::new());
+
+        assert_eq!(VecN::from([3.0, 4.0, 5.0]).to_cylindrical(UpAxis::Z), VecN::from([5.0, f32::atan2(4.0, 3.0), 5.0]));
+        assert_eq!(VecN::from([3.0, 5.0, 4.0]).to_cylindrical(UpAxis::Y), VecN::from([5.0, f32::atan2(3.0, 4.0), 5.0]));
+    }
+
+    #[test]
+    fn round_trips() {
+        let v = VecN::from([1.5, -2.0, 0.7]);
+        for convention in CONVENTIONS.iter() {
+            assert_eq!(VecN::<3>::from_spherical(&v.to_spherical(*convention), *convention), v);
+        }
+        for &up in [UpAxis::Y, UpAxis::Z].iter() {
+            assert_eq!(VecN::<3>::from_cylindrical(&v.to_cylindrical(up), up), v);
+        }
+    }
+
+    #[test]
+    fn jacobians() {
+        let h = 1e-3;
+        let coords = VecN::from([2.0, 0.7, -1.2]);
+        for convention in CONVENTIONS.iter() {
+            let jacobian = VecN::<3>::spherical_jacobian(&coords, *convention);
+            for j in 0..3 {
+                let mut step = VecN::<3>::new();
+                step[j] = h;
+                let numeric = (VecN::<3>::from_spherical(&(coords + step), *convention)
+                    - VecN::<3>::from_spherical(&(coords - step), *convention)) / (2.0 * h);
+                for i in 0..3 {
+                    assert!((numeric[i] - jacobian[i][j]).abs() < 1e-2, "{:?} {} {}", convention, i, j);
+                }
+            }
+        }
+        for &up in [UpAxis::Y, UpAxis::Z].iter() {
+            let jacobian = VecN::<3>::cylindrical_jacobian(&coords, up);
+            for j in 0..3 {
+                let mut step = VecN::<3>::new();
+                step[j] = h;
+                let numeric = (VecN::<3>::from_cylindrical(&(coords + step), up)
+                    - VecN::<3>::from_cylindrical(&(coords - step), up)) / (2.0 * h);
+                for i in 0..3 {
+                    assert!((numeric[i] - jacobian[i][j]).abs() < 1e-2);
+                }
+            }
+        }
+        // Determinant of the physics spherical jacobian is r^2 sin(theta)
+        let jacobian = VecN::<3>::spherical_jacobian(&coords, CONVENTIONS[0]);
+        assert!((jacobian.det() - 4.0 * f32::sin(0.7)).abs() < 1e-4);
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use my_matrix_lib::errors::MatrixError;
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn mat_errors() {
+        let x = Mat::from([[1.0, f32::NAN],
+                            [0.0, 1.0]]);
+        assert_eq!(x.inverse().unwrap_err(), MatrixError::NonFinite);
+        assert_eq!(x.exp().unwrap_err(), MatrixError::NonFinite);
+        let x = Mat::from([[f32::NAN, 0.0],
+                            [0.0, 1.0]]);
+        assert_eq!(x.exp().unwrap_err(), MatrixError::NonFinite);
+        assert_eq!((Mat::<2,2>::identity() * f32::INFINITY).exp().unwrap_err(), MatrixError::NonFinite);
+        // Negative eigenvalues have no real square root
+        let x = Mat::from([[-2.0, 0.0],
+                            [0.0, -3.0]]);
+        assert!(matches!(x.sqrt(), Err(MatrixError::NotConverged {iterations: 64, ..})));
+        assert_eq!(MatrixError::Singular.to_string(), "matrix is singular");
+    }
+
+    #[test]
+    fn vec_parse() {
+        assert_eq!("[1, 2.5, -3]".parse::<VecN<3>>(), Ok(VecN::from([1.0, 2.5, -3.0])));
+        assert_eq!("1 2".parse::<VecN<2>>(), Ok(VecN::from([1.0, 2.0])));
+        assert_eq!("1, 2".parse::<VecN<3>>(), Err(MatrixError::DimensionMismatch {expected: 3, found: 2}));
+        assert_eq!("1, x".parse::<VecN<2>>(), Err(MatrixError::ParseError {index: 1}));
+        assert_eq!("1 2 x".parse::<VecN<2>>(), Err(MatrixError::ParseError {index: 2}));
+        assert_eq!("1 2 3".parse::<VecN<2>>(), Err(MatrixError::DimensionMismatch {expected: 2, found: 3}));
+        assert_eq!(MatrixError::ParseError {index: 1}.to_string(), "value 1 is not a number");
+        assert_eq!(VecN::<2>::try_from(&[1.0, 2.0, 3.0][..]), Err(MatrixError::DimensionMismatch {expected: 2, found: 3}));
+    }
+}
+
+#[cfg(test)]
+mod simd_tests {
+    use my_matrix_lib::simd::{self, Backend};
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+
+    // Values that round differently depending on the order they are added in
+    fn awkward(i: usize) -> f32 {
+        (i as f32 * 0.37 + 0.1).sin() * 10f32.powi(i as i32 % 5 - 2)
+    }
+
+    #[test]
+    fn simd_matches_scalar_bits() {
+        if cfg!(all(feature = "simd", target_arch = "x86_64")) {
+            assert_ne!(simd::backend(), Backend::Scalar);
+        }
+
+        let a = Mat::<4,4>::from([[awkward(0), awkward(1), awkward(2), awkward(3)],
+                                   [awkward(4), awkward(5), awkward(6), awkward(7)],
+                                   [awkward(8), awkward(9), awkward(10), awkward(11)],
+                                   [awkward(12), awkward(13), awkward(14), awkward(15)]]);
+        let b = a.transpose() * 3.3;
+        let v = VecN::from([awkward(16), awkward(17), awkward(18), awkward(19)]);
+
+        let product = &a * &b;
+        let transformed = &a * v;
+        for i in 0..4 {
+            let mut row_dot = 0.0;
+            for j in 0..4 {
+                assert_eq!(b[j][i].to_bits(), (a[i][j] * 3.3).to_bits());
+                let mut expected = 0.0;
+                for k in 0..4 {
+                    expected += a[i][k] * b[k][j];
+                }
+                assert_eq!(product[i][j].to_bits(), expected.to_bits());
+                row_dot += a[i][j] * v[j];
+            }
+            assert_eq!(transformed[i].to_bits(), row_dot.to_bits());
+        }
+    }
+
+    #[test]
+    fn simd_elementwise_tails() {
+        // 11 covers an AVX2 block, an SSE2 block and a scalar tail
+        let x: VecN<11> = (0..11).map(awkward).collect();
+        let y: VecN<11> = (11..22).map(awkward).collect();
+        let sum = x + y;
+        let diff = x - y;
+        let scaled = x * 0.7;
+        for i in 0..11 {
+            assert_eq!(sum[i].to_bits(), (x[i] + y[i]).to_bits());
+            assert_eq!(diff[i].to_bits(), (x[i] - y[i]).to_bits());
+            assert_eq!(scaled[i].to_bits(), (x[i] * 0.7).to_bits());
+        }
+        let expected: f32 = (0..11).map(|i| x[i] * y[i]).sum();
+        assert!((x * y - expected).abs() < 1e-4);
+    }
+}
+
+#[cfg(test)]
+mod boxed_matrix_tests {
+    use my_matrix_lib::boxed_matrixs::BoxedMat;
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+
+    #[test]
+    fn boxed_mat_matches_mat() {
+        let x = Mat::from([[2.0, 5.0, 1.0],
+                            [-1.0, 0.0, 3.0],
+                            [4.0, 1.0, 1.0]]);
+        let boxed = BoxedMat::from(x.clone());
+        assert_eq!(*boxed.transpose(), x.transpose());
+        assert_eq!(*(&boxed * &boxed), &x * &x);
+        assert_eq!(*(&boxed + &boxed - &boxed * 0.5), &x * 1.5);
+        assert_eq!(*boxed.inverse().unwrap(), x.inverse().unwrap());
+        assert_eq!(*boxed.pow(-3).unwrap(), x.pow(-3).unwrap());
+        assert_eq!(&boxed * VecN::from([1.0, 2.0, 3.0]), &x * VecN::from([1.0, 2.0, 3.0]));
+        assert_eq!(boxed.clone() * VecN::from([1.0, 2.0, 3.0]), &x * VecN::from([1.0, 2.0, 3.0]));
+        // Elimination rounds differently from the cofactor expansion
+        assert!((boxed.det() - x.det()).abs() < 1e-4);
+        assert!(BoxedMat::<3,3>::new().inverse().is_err());
+        assert_eq!(BoxedMat::<3,3>::default(), BoxedMat::new());
+    }
+
+    #[test]
+    fn boxed_mat_functions_match_mat() {
+        let x = Mat::from([[2.0, 0.5, 0.0],
+                            [0.25, 1.5, 0.5],
+                            [0.0, 0.5, 3.0]]);
+        let boxed = BoxedMat::from(x.clone());
+        assert_eq!(*boxed.exp().unwrap(), x.exp().unwrap());
+        assert_eq!(*boxed.sqrt().unwrap(), x.sqrt().unwrap());
+        assert_eq!(*boxed.ln().unwrap(), x.ln().unwrap());
+        assert_eq!(*boxed.adjugate(), x.adjugate());
+        assert_eq!(*boxed.cofactor_matrix(), x.cofactor_matrix());
+        assert!((boxed.cofactor(1, 2) - x.cofactor(1, 2)).abs() < 1e-5);
+        for (a, b) in boxed.characteristic_polynomial().iter().zip(x.characteristic_polynomial()) {
+            assert!((a - b).abs() < 1e-4);
+        }
+
+        // Singular, so the adjugate has to come from the cofactors
+        let singular = Mat::from([[1.0, 2.0, 3.0],
+                                  [2.0, 4.0, 6.0],
+                                  [0.0, 1.0, 5.0]]);
+        assert_eq!(*BoxedMat::from(singular.clone()).adjugate(), singular.adjugate());
+        assert_eq!(BoxedMat::from(singular).det(), 0.0);
+    }
+
+    #[test]
+    fn boxed_mat_stays_off_the_stack() {
+        // Mat<512, 512> is 1MB, four times the stack this thread gets
+        let handle = std::thread::Builder::new().stack_size(256 * 1024).spawn(|| {
+            let a = BoxedMat::<512, 512>::from_fn(|i, j| if i == j { 2.0 } else { 0.0 });
+            let b = BoxedMat::<512, 512>::from_fn(|i, j| (i + 2 * j) as f32);
+            let t = b.transpose();
+            assert_eq!(t[3][5], 11.0);
+            let sum = &(&a + &b) - &t * 2.0;
+            assert_eq!(sum[5][3], -15.0);
+
+            let tall = BoxedMat::<512, 4>::from_fn(|i, j| (i * j) as f32);
+            let product = &b * &tall;
+            let mut expected = 0.0;
+            for k in 0..512 {
+                expected += b[7][k] * tall[k][2];
+            }
+            assert_eq!(product[7][2], expected);
+
+            let small = BoxedMat::<128, 128>::from_fn(|i, j| if i == j { 4.0 } else { 0.0 });
+            let inv = small.inverse().unwrap();
+            assert_eq!(inv[9][9], 0.25);
+            let scaled = BoxedMat::<128, 128>::from_fn(|i, j| if i != j { 0.0 } else if i == 9 { 4.0 } else { 1.0 });
+            assert_eq!(scaled.det(), 4.0);
+            assert_eq!(scaled.adjugate()[9][9], 1.0);
+            assert_eq!(scaled.adjugate()[0][0], 4.0);
+            let diagonal = BoxedMat::<128, 128>::from_fn(|i, j| if i == j { 0.5 } else { 0.0 });
+            assert!((diagonal.exp().unwrap()[9][9] - 0.5f32.exp()).abs() < 1e-6);
+            assert!((diagonal.sqrt().unwrap()[9][9] - 0.5f32.sqrt()).abs() < 1e-6);
+        }).unwrap();
+        handle.join().unwrap();
+    }
+}
+
+#[cfg(test)]
+mod col_matrix_tests {
+    use my_matrix_lib::col_matrixs::ColMat;
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+    use my_matrix_lib::errors::MatrixError;
+
+    #[test]
+    fn mat_slices() {
+        let mut x = Mat::from([[1.0, 2.0, 3.0],
+                                [4.0, 5.0, 6.0]]);
+        assert_eq!(x.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        x.as_mut_slice()[4] = 50.0;
+        assert_eq!(x[1][1], 50.0);
+        assert_eq!(Mat::<2,3>::from_row_major_slice(&[1.0, 2.0, 3.0, 4.0, 50.0, 6.0]), Ok(x.clone()));
+        assert_eq!(Mat::<2,3>::from_column_major_slice(&[1.0, 4.0, 2.0, 50.0, 3.0, 6.0]), Ok(x));
+        assert_eq!(Mat::<2,3>::from_row_major_slice(&[1.0; 5]).unwrap_err(), MatrixError::DimensionMismatch {expected: 6, found: 5});
+    }
+
+    #[test]
+    fn col_mat_layout() {
+        let x = Mat::from([[1.0, 2.0, 3.0],
+                            [4.0, 5.0, 6.0]]);
+        let mut col = ColMat::from(&x);
+        assert_eq!(col.as_slice(), &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+        assert_eq!(col[(0, 2)], 3.0);
+        assert_eq!(col.column(1), &VecN::from([2.0, 5.0]));
+        assert_eq!(col.get(2, 0), None);
+        assert_eq!(Mat::from(&col), x);
+        assert_eq!(ColMat::<2,3>::from_row_major_slice(x.as_slice()), Ok(col.clone()));
+        assert_eq!(ColMat::<2,3>::from_column_major_slice(col.as_slice()), Ok(col.clone()));
+
+        let v = VecN::from([1.0, -1.0, 2.0]);
+        assert_eq!(&col * v, &x * v);
+
+        col[(1, 0)] = 7.0;
+        let transposed = col.clone().into_transposed();
+        assert_eq!(transposed.as_slice(), col.as_slice());
+        assert_eq!(transposed[0][1], 7.0);
+        assert_eq!(ColMat::from_transposed(transposed), col);
+        assert_eq!(ColMat::<3,3>::identity().as_slice(), Mat::<3,3>::identity().as_slice());
+        assert_eq!(ColMat::<2,3>::default(), ColMat::new());
+    }
+
+    #[test]
+    fn col_mat_products() {
+        let a = Mat::from([[1.0, 2.0, 3.0],
+                            [4.0, 5.0, 6.0]]);
+        let b = Mat::from([[1.0, 0.0, 2.0, -1.0],
+                            [0.0, 1.0, 1.0, 0.0],
+                            [2.0, 1.0, 0.0, 1.0]]);
+        let product: ColMat<2,4> = ColMat::from(&a) * ColMat::from(&b);
+        assert_eq!(Mat::from(&product), &a * &b);
+        assert_eq!(&ColMat::<2,2>::identity() * &product, product);
+    }
+}
+
+#[cfg(test)]
+mod byte_tests {
+    use my_matrix_lib::bytes::*;
+    use my_matrix_lib::matrixs::Mat;
+    use my_matrix_lib::vectors::VecN;
+    use my_matrix_lib::errors::MatrixError;
+
+    #[test]
+    fn byte_round_trip() {
+        let points = vec![VecN::from([1.0, 2.0, 3.0]), VecN::from([4.0, 5.0, 6.0])];
+        let bytes = as_bytes(&points);
+        assert_eq!(bytes.len(), 24);
+        assert_eq!(&bytes[4..8], &2f32.to_ne_bytes());
+        assert_eq!(from_bytes::<VecN<3>>(bytes), Ok(&points[..]));
+
+        let mats = [Mat::<4,4>::identity()];
+        assert_eq!(as_bytes(&mats).len(), 64);
+        assert_eq!(from_bytes::<f32>(as_bytes(&mats)).unwrap(), Mat::<4,4>::identity().as_slice());
+
+        let mut buffer = vec![0f32; 5];
+        assert_eq!(from_bytes::<VecN<3>>(as_bytes(&buffer[..4])), Err(MatrixError::ByteLength {element_size: 12, found: 16}));
+        let raw = unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, 20) };
+        assert_eq!(from_bytes::<VecN<2>>(&raw[1..9]), Err(MatrixError::Misaligned {alignment: 4}));
+        from_bytes_mut::<VecN<2>>(&mut raw[4..20]).unwrap()[1][0] = 9.0;
+        assert_eq!(buffer[3], 9.0);
+    }
+
+    #[test]
+    fn gpu_layouts() {
+        assert_eq!(std::mem::size_of::<PaddedVec3>(), 16);
+        assert_eq!(std::mem::size_of::<PaddedMat3>(), 48);
+
+        let padded = pad_vec3s(&[VecN::from([1.0, 2.0, 3.0]), VecN::from([4.0, 5.0, 6.0])]);
+        let floats = from_bytes::<f32>(as_bytes(&padded)).unwrap();
+        assert_eq!(floats, &[1.0, 2.0, 3.0, 0.0, 4.0, 5.0, 6.0, 0.0]);
+
+        let mat = Mat::from([[1.0, 2.0, 3.0],
+                              [4.0, 5.0, 6.0],
+                              [7.0, 8.0, 9.0]]);
+        let padded = PaddedMat3::from(&mat);
+        let floats = from_bytes::<f32>(as_bytes(std::slice::from_ref(&padded))).unwrap();
+        assert_eq!(floats, &[1.0, 4.0, 7.0, 0.0, 2.0, 5.0, 8.0, 0.0, 3.0, 6.0, 9.0, 0.0]);
+        assert_eq!(Mat::from(&padded), mat);
+    }
+}
+
+#[cfg(test)]
+mod const_tests {
+    use my_matrix_lib::vectors::{VecN, Vec3};
+    use my_matrix_lib::matrixs::{Mat, Mat3, Mat4};
+    use my_matrix_lib::col_matrixs::ColMat;
+
+    const IDENTITY: Mat4 = Mat4::IDENTITY;
+    const SHEAR: Mat<2, 3> = Mat::from_array([[1.0, 2.0, 3.0],
+                                              [4.0, 5.0, 6.0]]);
+    const SHEAR_T: Mat<3, 2> = SHEAR.transpose_const();
+    static CORNERS: [Vec3; 2] = [VecN::from_array([-1.0, -1.0, -1.0]), Vec3::ONE];
+
+    #[test]
+    fn compile_time_constants() {
+        assert_eq!(IDENTITY, Mat4::identity());
+        assert_eq!(Mat3::ZERO, Mat3::new());
+        assert_eq!(VecN::<5>::ZERO, VecN::new());
+        assert_eq!(VecN::<2>::ONE, VecN::from([1.0, 1.0]));
+        assert_eq!(SHEAR_T, Mat::from([[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]));
+        assert_eq!(CORNERS[1], VecN::from([1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn const_component_access() {
+        const X: f32 = Vec3::Z.z();
+        const ENTRY: Option<&f32> = SHEAR.get(1, 2);
+        const OUTSIDE: Option<&f32> = SHEAR.get(2, 0);
+        assert_eq!(X, 1.0);
+        assert_eq!(ENTRY, Some(&6.0));
+        assert_eq!(OUTSIDE, None);
+        assert_eq!(VecN::from_array([1.0, 2.0]).get(2), None);
+
+        const COLUMNS: ColMat<2, 2> = ColMat::identity().transpose_const();
+        assert_eq!(COLUMNS.get(1, 1), Some(&1.0));
+    }
+
+    #[test]
+    fn const_transpose_4x4() {
+        let mat = Mat4::from_array([[1.0, 2.0, 3.0, 4.0],
+                                    [5.0, 6.0, 7.0, 8.0],
+                                    [9.0, 10.0, 11.0, 12.0],
+                                    [13.0, 14.0, 15.0, 16.0]]);
+        // The runtime version goes through the SIMD kernel at this size
+        assert_eq!(mat.transpose_const().transpose_const(), mat);
+        assert_eq!(mat.transpose_const()[1][3], 14.0);
+        assert_eq!(mat.transpose_const().as_slice(), mat.transpose().as_slice());
+    }
+}
@@ -0,0 +1,81 @@
+// The f32 functions that only exist with std. Without std this trait puts them back as
+// methods on f32, backed by libm (a pure Rust port of musl's math library), so the rest
+// of the crate can keep calling f32::sqrt(x) or x.sqrt() either way. Modules import it
+// with #[cfg(not(feature = "std"))] since with std the inherent methods are used.
+#[cfg(not(feature = "std"))]
+pub(crate) trait Float {
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn sin_cos(self) -> (Self, Self) where Self: Sized;
+    fn hypot(self, other: Self) -> Self;
+    fn ceil(self) -> Self;
+    fn log2(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+}
+
+#[cfg(not(feature = "std"))]
+impl Float for f32 {
+    fn sqrt(self) -> f32 {
+        libm::sqrtf(self)
+    }
+
+    fn sin(self) -> f32 {
+        libm::sinf(self)
+    }
+
+    fn cos(self) -> f32 {
+        libm::cosf(self)
+    }
+
+    fn tan(self) -> f32 {
+        libm::tanf(self)
+    }
+
+    fn asin(self) -> f32 {
+        libm::asinf(self)
+    }
+
+    fn acos(self) -> f32 {
+        libm::acosf(self)
+    }
+
+    fn atan2(self, other: f32) -> f32 {
+        libm::atan2f(self, other)
+    }
+
+    fn sin_cos(self) -> (f32, f32) {
+        libm::sincosf(self)
+    }
+
+    fn hypot(self, other: f32) -> f32 {
+        libm::hypotf(self, other)
+    }
+
+    fn ceil(self) -> f32 {
+        libm::ceilf(self)
+    }
+
+    fn log2(self) -> f32 {
+        libm::log2f(self)
+    }
+
+    // Repeated squaring like the std version, which also skips pow for integer powers
+    fn powi(self, n: i32) -> f32 {
+        let mut base = if n < 0 { 1.0 / self } else { self };
+        let mut exp = n.unsigned_abs();
+        let mut answer = 1.0;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                answer *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        answer
+    }
+}
@@ -1,42 +1,298 @@
-use std::ops::{Mul,Div,Index,IndexMut};
-use crate::vectors::VecN;
-
+use core::ops::{Add,Sub,Mul,Div,Index,IndexMut,Range};
+use crate::vectors::{VecN, Unit};
+use crate::points::Point;
+use crate::errors::MatrixError;
+use crate::simd::{self, Mat4Rows};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+
+// Row major, each VecN is one row. repr(transparent) all the way down means the values
+// are N * M f32s back to back.
 #[derive(Clone, Debug)]
+#[repr(transparent)]
 pub struct Mat<const N: usize, const M: usize> {
     vals: [VecN<M>; N],
 }
 
+pub type Mat2 = Mat<2, 2>;
+pub type Mat3 = Mat<3, 3>;
+pub type Mat4 = Mat<4, 4>;
+
 impl<const N: usize, const M: usize> Mat<N, M> {
-    pub fn new() -> Mat<N, M> {
-        Mat {vals: [VecN::<M>::new(); N]}
+    pub const ZERO: Mat<N, M> = Mat::new();
+
+    pub const fn new() -> Mat<N, M> {
+        Mat {vals: [VecN::<M>::ZERO; N]}
     }
 
-    pub fn transpose(&self) -> Mat<M, N> {
-        let mut new_mat = Mat::<M, N>::new();
+    // Same as the From conversions but usable in consts and statics.
+    // Trait methods cannot be const so these are plain loops.
+    pub const fn from_array(vals: [[f32; M]; N]) -> Mat<N, M> {
+        let mut mat = Mat::new();
+        let mut i = 0;
+        while i < N {
+            mat.vals[i] = VecN::from_array(vals[i]);
+            i += 1;
+        }
+        mat
+    }
+
+    pub const fn from_rows(vals: [VecN<M>; N]) -> Mat<N, M> {
+        Mat {vals}
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.vals.iter().all(|row| row.is_finite())
+    }
+
+    // Fails unless there are exactly N * M values
+    pub fn from_row_major_slice(vals: &[f32]) -> Result<Mat<N, M>, MatrixError> {
+        if vals.len() != N * M {
+            return Err(MatrixError::DimensionMismatch {expected: N * M, found: vals.len()});
+        }
+        let mut mat = Mat::new();
+        mat.as_mut_slice().copy_from_slice(vals);
+        Ok(mat)
+    }
+
+    pub fn from_column_major_slice(vals: &[f32]) -> Result<Mat<N, M>, MatrixError> {
+        if vals.len() != N * M {
+            return Err(MatrixError::DimensionMismatch {expected: N * M, found: vals.len()});
+        }
+        let mut mat = Mat::new();
         for i in 0..N {
             for j in 0..M {
-                new_mat[j][i] = self[i][j];
+                mat[i][j] = vals[j * N + i];
             }
         }
+        Ok(mat)
+    }
+
+    // All the values in row major order
+    pub fn as_slice(&self) -> &[f32] {
+        unsafe { core::slice::from_raw_parts(self.vals.as_ptr() as *const f32, N * M) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [f32] {
+        unsafe { core::slice::from_raw_parts_mut(self.vals.as_mut_ptr() as *mut f32, N * M) }
+    }
+
+    // Element at row i and column j, None when out of bounds
+    pub const fn get(&self, i: usize, j: usize) -> Option<&f32> {
+        if i < N && j < M {
+            Some(&self.vals[i].vals[j])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut f32> {
+        self.vals.get_mut(i)?.get_mut(j)
+    }
+
+    /// Skips the bounds checks.
+    ///
+    /// # Safety
+    /// i must be less than N and j less than M
+    pub unsafe fn get_unchecked(&self, i: usize, j: usize) -> &f32 {
+        self.vals.get_unchecked(i).get_unchecked(j)
+    }
+
+    /// # Safety
+    /// i must be less than N and j less than M
+    pub unsafe fn get_unchecked_mut(&mut self, i: usize, j: usize) -> &mut f32 {
+        self.vals.get_unchecked_mut(i).get_unchecked_mut(j)
+    }
+
+    pub fn transpose(&self) -> Mat<M, N> {
+        if N == 4 && M == 4 {
+            return Mat::from_rows4(&simd::transpose4(&self.to_rows4()));
+        }
+        self.transpose_const()
+    }
+
+    // Same result as transpose but usable in consts and statics, without the SIMD kernel
+    pub const fn transpose_const(&self) -> Mat<M, N> {
+        let mut new_mat = Mat::<M, N>::new();
+        let mut i = 0;
+        while i < N {
+            let mut j = 0;
+            while j < M {
+                new_mat.vals[j].vals[i] = self.vals[i].vals[j];
+                j += 1;
+            }
+            i += 1;
+        }
         new_mat
     }
+
+    // out = self * other without building any temporaries
+    pub fn mul_into<const Z: usize>(&self, other: &Mat<M, Z>, out: &mut Mat<N, Z>) {
+        gemm(1.0, self, other, 0.0, out);
+    }
+
+    // Only used for 4x4 matrices to hand them to the SIMD kernels
+    fn to_rows4(&self) -> Mat4Rows {
+        let mut rows = [[0.0; 4]; 4];
+        for (row, vals) in rows.iter_mut().zip(self.vals.iter()) {
+            row.copy_from_slice(&vals[..]);
+        }
+        rows
+    }
+
+    fn from_rows4(rows: &Mat4Rows) -> Mat<N, M> {
+        let mut mat = Mat::new();
+        for (vals, row) in mat.vals.iter_mut().zip(rows.iter()) {
+            vals[..].copy_from_slice(row);
+        }
+        mat
+    }
 }
 
 impl<const N:usize> Mat<N, N> {
-    pub fn identity() -> Mat<N, N> {
+    pub const IDENTITY: Mat<N, N> = Mat::identity();
+
+    pub const fn identity() -> Mat<N, N> {
         let mut mat = Self::new();
-        for i in 0..N {
-            mat[i][i] = 1.0;
+        let mut i = 0;
+        while i < N {
+            mat.vals[i].vals[i] = 1.0;
+            i += 1;
         }
         mat
     }
     
+    #[cfg(feature = "alloc")]
     pub fn det(&self) -> f32 {
         determinent(self.into())  
     }
 
+    pub fn trace(&self) -> f32 {
+        let mut answer = 0.0;
+        for i in 0..N {
+            answer += self[i][i];
+        }
+        answer
+    }
+
+    // Determinant of the matrix left after removing row i and column j
+    #[cfg(feature = "alloc")]
+    pub fn minor(&self, i: usize, j: usize) -> f32 {
+        assert!(i < N && j < N, "minor index ({}, {}) out of bounds for {}x{} matrix", i, j, N, N);
+        if N == 1 {
+            return 1.0;
+        }
+
+        let mut vals = Vec::<Vec<f32>>::with_capacity(N - 1);
+        for row in (0..N).filter(|&row| row != i) {
+            vals.push((0..N).filter(|&col| col != j).map(|col| self[row][col]).collect());
+        }
+        determinent(vals)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn cofactor(&self, i: usize, j: usize) -> f32 {
+        if (i + j) % 2 == 1 {
+            -self.minor(i, j)
+        } else {
+            self.minor(i, j)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn cofactor_matrix(&self) -> Mat<N, N> {
+        let mut new_mat = Mat::new();
+        for i in 0..N {
+            for j in 0..N {
+                new_mat[i][j] = self.cofactor(i, j);
+            }
+        }
+        new_mat
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn adjugate(&self) -> Mat<N, N> {
+        self.cofactor_matrix().transpose()
+    }
+
+    // Coefficients of det(tI - A) found with Faddeev-LeVerrier.
+    // Index k holds the coefficient of t^k so the last value is always 1.
+    #[cfg(feature = "alloc")]
+    pub fn characteristic_polynomial(&self) -> Vec<f32> {
+        let mut coeffs = vec![0.0; N + 1];
+        characteristic_polynomial_in_place(self, &mut coeffs, [&mut Mat::new(), &mut Mat::new()]);
+        coeffs
+    }
+
+    // Gauss-Jordan elimination with partial pivoting
+    pub fn inverse(&self) -> Result<Mat<N, N>, MatrixError> {
+        let mut mat = self.clone();
+        let mut inv = Mat::<N, N>::identity();
+        invert_in_place(&mut mat, &mut inv)?;
+        Ok(inv)
+    }
+
+    // Repeated squaring, negative powers go through the inverse
+    pub fn pow(&self, exp: i32) -> Result<Mat<N, N>, MatrixError> {
+        let mut base = if exp < 0 {
+            self.inverse()?
+        } else {
+            self.clone()
+        };
+        let mut exp = exp.unsigned_abs();
+        let mut answer = Mat::identity();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                answer = &answer * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+        Ok(answer)
+    }
+
+    // Scaling and squaring with a degree 6 Pade approximant
+    pub fn exp(&self) -> Result<Mat<N, N>, MatrixError> {
+        let mut answer = Mat::new();
+        exp_in_place(self, &mut answer, [&mut Mat::new(), &mut Mat::new(), &mut Mat::new(), &mut Mat::new()])?;
+        Ok(answer)
+    }
+
+    // Principal square root using the Denman-Beavers iteration
+    pub fn sqrt(&self) -> Result<Mat<N, N>, MatrixError> {
+        let mut answer = Mat::new();
+        sqrt_in_place(self, &mut answer, [&mut Mat::new(), &mut Mat::new(), &mut Mat::new(), &mut Mat::new()])?;
+        Ok(answer)
+    }
+
+    // Principal logarithm by inverse scaling and squaring, the matrix must not
+    // have eigenvalues on the closed negative real axis
+    pub fn ln(&self) -> Result<Mat<N, N>, MatrixError> {
+        let mut answer = Mat::new();
+        ln_in_place(self, &mut answer, [&mut Mat::new(), &mut Mat::new(), &mut Mat::new(), &mut Mat::new(), &mut Mat::new()])?;
+        Ok(answer)
+    }
+
+    // Max absolute column sum
+    pub(crate) fn norm_1(&self) -> f32 {
+        let mut answer: f32 = 0.0;
+        for j in 0..N {
+            let mut sum = 0.0;
+            for i in 0..N {
+                sum += self[i][j].abs();
+            }
+            answer = answer.max(sum);
+        }
+        answer
+    }
+
 }
 
+pub(crate) const MAX_ITERATIONS: usize = 64;
+
 impl Mat<2,2> {
     pub fn rotation(theta: f32) -> Mat<2,2> {
         let cos = f32::cos(theta);
@@ -46,6 +302,203 @@ impl Mat<2,2> {
     }
 }
 
+// Which range clip space depth is mapped to, OpenGL uses -1..1 while Vulkan, D3D and Metal use 0..1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DepthRange {
+    NegativeOneToOne,
+    ZeroToOne,
+}
+
+impl Mat<3,3> {
+    pub fn rotation_x(theta: f32) -> Mat<3,3> {
+        let cos = f32::cos(theta);
+        let sin = f32::sin(theta);
+        Mat::from([[1.0, 0.0, 0.0],
+                    [0.0, cos, -sin],
+                    [0.0, sin, cos]])
+    }
+
+    pub fn rotation_y(theta: f32) -> Mat<3,3> {
+        let cos = f32::cos(theta);
+        let sin = f32::sin(theta);
+        Mat::from([[cos, 0.0, sin],
+                    [0.0, 1.0, 0.0],
+                    [-sin, 0.0, cos]])
+    }
+
+    pub fn rotation_z(theta: f32) -> Mat<3,3> {
+        let cos = f32::cos(theta);
+        let sin = f32::sin(theta);
+        Mat::from([[cos, -sin, 0.0],
+                    [sin, cos, 0.0],
+                    [0.0, 0.0, 1.0]])
+    }
+
+    // The axis does not need to be normalized, a zero axis gives the identity
+    pub fn rotation_axis_angle(axis: &VecN<3>, theta: f32) -> Mat<3,3> {
+        match Unit::try_new(*axis, 0.0) {
+            Some(axis) => Mat::rotation_unit_axis_angle(&axis, theta),
+            None => Mat::identity(),
+        }
+    }
+
+    // Rodrigues' rotation formula
+    pub fn rotation_unit_axis_angle(axis: &Unit<VecN<3>>, theta: f32) -> Mat<3,3> {
+        let k = **axis;
+        let cos = f32::cos(theta);
+        let sin = f32::sin(theta);
+        let t = 1.0 - cos;
+        Mat::from([[cos + t * k[0] * k[0], t * k[0] * k[1] - sin * k[2], t * k[0] * k[2] + sin * k[1]],
+                    [t * k[1] * k[0] + sin * k[2], cos + t * k[1] * k[1], t * k[1] * k[2] - sin * k[0]],
+                    [t * k[2] * k[0] - sin * k[1], t * k[2] * k[1] + sin * k[0], cos + t * k[2] * k[2]]])
+    }
+
+    // Pulls a rotation matrix that has drifted from repeated products back to a true rotation.
+    // The first column keeps its direction, the second is made perpendicular to it and the
+    // third is rebuilt with a cross product so the result stays right handed.
+    pub fn orthonormalize(&self) -> Mat<3,3> {
+        let cols = self.transpose();
+        let x = cols[0].normalize();
+        let y = cols[1].reject(&x).normalize();
+        let z = x.cross(&y);
+        Mat::from([x, y, z]).transpose()
+    }
+
+    // 2D homogeneous translation
+    pub fn translation(offset: &VecN<2>) -> Mat<3,3> {
+        Mat::from([[1.0, 0.0, offset[0]],
+                    [0.0, 1.0, offset[1]],
+                    [0.0, 0.0, 1.0]])
+    }
+
+    // 2D homogeneous rotation
+    pub fn rotation(theta: f32) -> Mat<3,3> {
+        let cos = f32::cos(theta);
+        let sin = f32::sin(theta);
+        Mat::from([[cos, -sin, 0.0],
+                    [sin, cos, 0.0],
+                    [0.0, 0.0, 1.0]])
+    }
+
+    // Applies the full 2D homogeneous transform including the perspective divide
+    pub fn transform_point(&self, point: &Point<2>) -> Point<2> {
+        Point::from(VecN::<2>::from_homogeneous(&(self * point.vals.to_homogeneous())))
+    }
+
+    // Directions ignore the translation column
+    pub fn transform_vector(&self, vector: &VecN<2>) -> VecN<2> {
+        VecN::from([self[0][0] * vector[0] + self[0][1] * vector[1],
+                    self[1][0] * vector[0] + self[1][1] * vector[1]])
+    }
+}
+
+impl Mat<4,4> {
+    pub fn translation(offset: &VecN<3>) -> Mat<4,4> {
+        Mat::from([[1.0, 0.0, 0.0, offset[0]],
+                    [0.0, 1.0, 0.0, offset[1]],
+                    [0.0, 0.0, 1.0, offset[2]],
+                    [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    pub fn scaling(scale: f32) -> Mat<4,4> {
+        Mat::non_uniform_scaling(&VecN::from([scale, scale, scale]))
+    }
+
+    pub fn non_uniform_scaling(scale: &VecN<3>) -> Mat<4,4> {
+        Mat::from([[scale[0], 0.0, 0.0, 0.0],
+                    [0.0, scale[1], 0.0, 0.0],
+                    [0.0, 0.0, scale[2], 0.0],
+                    [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    // xy is how far x moves per unit of y and so on
+    pub fn shear(xy: f32, xz: f32, yx: f32, yz: f32, zx: f32, zy: f32) -> Mat<4,4> {
+        Mat::from([[1.0, xy, xz, 0.0],
+                    [yx, 1.0, yz, 0.0],
+                    [zx, zy, 1.0, 0.0],
+                    [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    // View matrix for a right handed system, the camera looks down -z
+    pub fn look_at_rh(eye: &VecN<3>, target: &VecN<3>, up: &VecN<3>) -> Mat<4,4> {
+        let f = (target - eye).normalize();
+        let s = f.cross(up).normalize();
+        let u = s.cross(&f);
+        Mat::from([[s[0], s[1], s[2], -(s * eye)],
+                    [u[0], u[1], u[2], -(u * eye)],
+                    [-f[0], -f[1], -f[2], f * eye],
+                    [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    // View matrix for a left handed system, the camera looks down +z
+    pub fn look_at_lh(eye: &VecN<3>, target: &VecN<3>, up: &VecN<3>) -> Mat<4,4> {
+        let f = (target - eye).normalize();
+        let s = up.cross(&f).normalize();
+        let u = f.cross(&s);
+        Mat::from([[s[0], s[1], s[2], -(s * eye)],
+                    [u[0], u[1], u[2], -(u * eye)],
+                    [f[0], f[1], f[2], -(f * eye)],
+                    [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    // Right handed perspective projection, fov_y is in radians
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32, depth: DepthRange) -> Mat<4,4> {
+        let f = 1.0 / f32::tan(fov_y / 2.0);
+        let (c, d) = match depth {
+            DepthRange::NegativeOneToOne => ((far + near) / (near - far), 2.0 * far * near / (near - far)),
+            DepthRange::ZeroToOne => (far / (near - far), far * near / (near - far)),
+        };
+        Mat::from([[f / aspect, 0.0, 0.0, 0.0],
+                    [0.0, f, 0.0, 0.0],
+                    [0.0, 0.0, c, d],
+                    [0.0, 0.0, -1.0, 0.0]])
+    }
+
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32, depth: DepthRange) -> Mat<4,4> {
+        let (c, d) = match depth {
+            DepthRange::NegativeOneToOne => (-2.0 / (far - near), -(far + near) / (far - near)),
+            DepthRange::ZeroToOne => (-1.0 / (far - near), -near / (far - near)),
+        };
+        Mat::from([[2.0 / (right - left), 0.0, 0.0, -(right + left) / (right - left)],
+                    [0.0, 2.0 / (top - bottom), 0.0, -(top + bottom) / (top - bottom)],
+                    [0.0, 0.0, c, d],
+                    [0.0, 0.0, 0.0, 1.0]])
+    }
+
+    pub fn frustum(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32, depth: DepthRange) -> Mat<4,4> {
+        let (c, d) = match depth {
+            DepthRange::NegativeOneToOne => (-(far + near) / (far - near), -2.0 * far * near / (far - near)),
+            DepthRange::ZeroToOne => (-far / (far - near), -far * near / (far - near)),
+        };
+        Mat::from([[2.0 * near / (right - left), 0.0, (right + left) / (right - left), 0.0],
+                    [0.0, 2.0 * near / (top - bottom), (top + bottom) / (top - bottom), 0.0],
+                    [0.0, 0.0, c, d],
+                    [0.0, 0.0, -1.0, 0.0]])
+    }
+
+    pub fn linear_part(&self) -> Mat<3,3> {
+        Mat::from([[self[0][0], self[0][1], self[0][2]],
+                    [self[1][0], self[1][1], self[1][2]],
+                    [self[2][0], self[2][1], self[2][2]]])
+    }
+
+    // Applies the full transform including the perspective divide
+    pub fn transform_point(&self, point: &Point<3>) -> Point<3> {
+        Point::from(VecN::<3>::from_homogeneous(&(self * point.vals.to_homogeneous())))
+    }
+
+    // Directions ignore the translation column
+    pub fn transform_vector(&self, vector: &VecN<3>) -> VecN<3> {
+        self.linear_part() * vector
+    }
+
+    // Inverse transpose of the upper 3x3, keeps normals perpendicular to surfaces
+    // under non-uniform scaling
+    pub fn normal_matrix(&self) -> Result<Mat<3,3>, MatrixError> {
+        Ok(self.linear_part().inverse()?.transpose())
+    }
+}
+
 impl<const N: usize, const M: usize> Index<usize> for Mat<N, M> {
     type Output = VecN<M>;
 
@@ -60,19 +513,30 @@ impl<const N: usize, const M: usize> IndexMut<usize> for Mat<N, M> {
     }
 }
 
+// m[(i, j)] is the same as m[i][j]
+impl<const N: usize, const M: usize> Index<(usize, usize)> for Mat<N, M> {
+    type Output = f32;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f32 {
+        &self.vals[i][j]
+    }
+}
+
+impl<const N: usize, const M: usize> IndexMut<(usize, usize)> for Mat<N, M> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f32 {
+        &mut self.vals[i][j]
+    }
+}
+
 impl<const N: usize, const M: usize> From<[[f32; M]; N]> for Mat<N, M> {
     fn from(vals: [[f32; M]; N]) -> Self {
-        let mut new_mat = Self::new();
-        for i in 0..N {
-            new_mat[i] = vals[i].into();
-        }
-        new_mat
+        Mat::from_array(vals)
     }
 }
 
 impl<const N: usize, const M: usize> From<[VecN<M>; N]> for Mat<N, M> {
     fn from(vals: [VecN<M>; N]) -> Self {
-        Mat {vals}
+        Mat::from_rows(vals)
     }
 }
 
@@ -96,13 +560,11 @@ impl<const N: usize, const M: usize> Mul<&f32> for &Mat<N, M> {
     type Output = Mat<N, M>;
 
     fn mul(self, scalar: &f32) -> Mat<N, M> {
-        let mut new_mat = [[0.0; M]; N];
+        let mut new_mat = Mat::new();
         for i in 0..N {
-            for j in 0..M {
-                new_mat[i][j] = self.vals[i][j] * scalar;
-            }
+            new_mat[i] = self[i] * scalar;
         }
-        Mat::from(new_mat)
+        new_mat
     }
 }
 
@@ -172,41 +634,113 @@ impl<const N: usize, const M: usize> Mul<f32> for Mat<N, M> {
 }
 
 
-impl<const N: usize, const M: usize, const Z: usize> Mul<&Mat<M, Z>> for &Mat<N, M> {
+impl<const N: usize, const M: usize> Add<&Mat<N, M>> for &Mat<N, M> {
     type Output = Mat<N, M>;
 
-    fn mul(self, other: &Mat<M, Z>) -> Mat<N, M> {
+    fn add(self, other: &Mat<N, M>) -> Mat<N, M> {
         let mut new_mat = Mat::new();
-        let other_t = other.transpose();
         for i in 0..N {
-            for j in 0..Z {
-                new_mat[i][j] = self[i] * &other_t[j];
-            }
+            new_mat[i] = self[i] + other[i];
         }
         new_mat
     }
 }
 
-impl<const N: usize, const M: usize, const Z: usize> Mul<&Mat<M, Z>> for Mat<N, M> {
+impl<const N: usize, const M: usize> Add<Mat<N, M>> for &Mat<N, M> {
+    type Output = Mat<N, M>;
+
+    fn add(self, other: Mat<N, M>) -> Mat<N, M> {
+        self + &other
+    }
+}
+
+impl<const N: usize, const M: usize> Add<&Mat<N, M>> for Mat<N, M> {
+    type Output = Mat<N, M>;
+
+    fn add(self, other: &Mat<N, M>) -> Mat<N, M> {
+        &self + other
+    }
+}
+
+impl<const N: usize, const M: usize> Add<Mat<N, M>> for Mat<N, M> {
+    type Output = Mat<N, M>;
+
+    fn add(self, other: Mat<N, M>) -> Mat<N, M> {
+        &self + &other
+    }
+}
+
+
+impl<const N: usize, const M: usize> Sub<&Mat<N, M>> for &Mat<N, M> {
     type Output = Mat<N, M>;
 
-    fn mul(self, other: &Mat<M, Z>) -> Mat<N, M> {
+    fn sub(self, other: &Mat<N, M>) -> Mat<N, M> {
+        let mut new_mat = Mat::new();
+        for i in 0..N {
+            new_mat[i] = self[i] - other[i];
+        }
+        new_mat
+    }
+}
+
+impl<const N: usize, const M: usize> Sub<Mat<N, M>> for &Mat<N, M> {
+    type Output = Mat<N, M>;
+
+    fn sub(self, other: Mat<N, M>) -> Mat<N, M> {
+        self - &other
+    }
+}
+
+impl<const N: usize, const M: usize> Sub<&Mat<N, M>> for Mat<N, M> {
+    type Output = Mat<N, M>;
+
+    fn sub(self, other: &Mat<N, M>) -> Mat<N, M> {
+        &self - other
+    }
+}
+
+impl<const N: usize, const M: usize> Sub<Mat<N, M>> for Mat<N, M> {
+    type Output = Mat<N, M>;
+
+    fn sub(self, other: Mat<N, M>) -> Mat<N, M> {
+        &self - &other
+    }
+}
+
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<&Mat<M, Z>> for &Mat<N, M> {
+    type Output = Mat<N, Z>;
+
+    fn mul(self, other: &Mat<M, Z>) -> Mat<N, Z> {
+        if N == 4 && M == 4 && Z == 4 {
+            return Mat::from_rows4(&simd::mat4_mul(&self.to_rows4(), &other.to_rows4()));
+        }
+        let mut new_mat = Mat::new();
+        gemm(1.0, self, other, 0.0, &mut new_mat);
+        new_mat
+    }
+}
+
+impl<const N: usize, const M: usize, const Z: usize> Mul<&Mat<M, Z>> for Mat<N, M> {
+    type Output = Mat<N, Z>;
+
+    fn mul(self, other: &Mat<M, Z>) -> Mat<N, Z> {
         &self * other
     }
 }
 
 impl<const N: usize, const M: usize, const Z: usize> Mul<Mat<M, Z>> for Mat<N, M> {
-    type Output = Mat<N, M>;
+    type Output = Mat<N, Z>;
 
-    fn mul(self, other: Mat<M, Z>) -> Mat<N, M> {
+    fn mul(self, other: Mat<M, Z>) -> Mat<N, Z> {
         &self * &other
     }
 }
 
 impl<const N: usize, const M: usize, const Z: usize> Mul<Mat<M, Z>> for &Mat<N, M> {
-    type Output = Mat<N, M>;
+    type Output = Mat<N, Z>;
 
-    fn mul(self, other: Mat<M, Z>) -> Mat<N, M> {
+    fn mul(self, other: Mat<M, Z>) -> Mat<N, Z> {
         self * &other
     }
 }
@@ -218,6 +752,12 @@ impl<const N: usize, const M: usize> Mul<&VecN<M>> for &Mat<N, M> {
 
     fn mul(self, other: &VecN<M>) -> VecN<N> {
         let mut new_vec = VecN::new();
+        if N == 4 && M == 4 {
+            let mut vals = [0.0; 4];
+            vals.copy_from_slice(&other[..]);
+            new_vec[..].copy_from_slice(&simd::mat4_vec(&self.to_rows4(), &vals));
+            return new_vec;
+        }
         for i in 0..N {
             new_vec[i] = self[i] * other;
         }
@@ -250,6 +790,7 @@ impl<const N: usize, const M: usize> Mul<VecN<M>> for &Mat<N, M> {
 }
 
 
+#[cfg(feature = "alloc")]
 impl<const N: usize, const M: usize> From<&Vec<&Vec<f32>>> for Mat<N, M> {
     fn from(vals: &Vec<&Vec<f32>>) -> Self {
         let mut mat = Mat::new();
@@ -262,6 +803,7 @@ impl<const N: usize, const M: usize> From<&Vec<&Vec<f32>>> for Mat<N, M> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<const N: usize, const M: usize> From<&Mat<N, M>> for Vec<Vec<f32>> {
     fn from(mat: &Mat<N, M>) -> Self {
         let mut vals: Vec<Vec<f32>> = Vec::with_capacity(N);
@@ -286,6 +828,298 @@ impl<const N: usize, const M: usize> PartialEq for Mat<N, M> {
 
 
 
+// Turns mat into the identity and inv, which has to start as the identity, into the
+// inverse. Shared with BoxedMat so neither copy has to live on the stack.
+pub(crate) fn invert_in_place<const N: usize>(mat: &mut Mat<N, N>, inv: &mut Mat<N, N>) -> Result<(), MatrixError> {
+    if !mat.is_finite() {
+        return Err(MatrixError::NonFinite);
+    }
+    // Taken once from the matrix as given since the pivot rows get scaled to 1 along the way.
+    // Each column gets its own so scaling a column of the input scales its tolerance with it,
+    // the rows that are not pivots yet stay in the units of the original columns.
+    let mut tolerance = [0.0; N];
+    for row in mat.vals.iter() {
+        for (col, val) in row.vals.iter().enumerate() {
+            tolerance[col] += val.abs();
+        }
+    }
+    for val in tolerance.iter_mut() {
+        *val *= f32::EPSILON * N as f32;
+    }
+
+    for col in 0..N {
+        let mut pivot = col;
+        for row in (col + 1)..N {
+            if mat[row][col].abs() > mat[pivot][col].abs() {
+                pivot = row;
+            }
+        }
+        if mat[pivot][col].abs() <= tolerance[col] {
+            return Err(MatrixError::Singular);
+        }
+        mat.vals.swap(col, pivot);
+        inv.vals.swap(col, pivot);
+
+        let scale = mat[col][col];
+        mat[col] = mat[col] / scale;
+        inv[col] = inv[col] / scale;
+
+        for row in 0..N {
+            if row != col {
+                let factor = mat[row][col];
+                mat[row] = mat[row] - mat[col] * factor;
+                inv[row] = inv[row] - inv[col] * factor;
+            }
+        }
+    }
+    Ok(())
+}
+
+// The functions below write into the buffers they are given and never make a Mat of their
+// own, so BoxedMat can hand them heap memory. What is left in the work buffers is garbage.
+
+fn set_identity<const N: usize>(mat: &mut Mat<N, N>) {
+    for (i, row) in mat.vals.iter_mut().enumerate() {
+        *row = VecN::new();
+        row[i] = 1.0;
+    }
+}
+
+// dst += src * scale
+fn add_scaled<const N: usize>(dst: &mut Mat<N, N>, src: &Mat<N, N>, scale: f32) {
+    for (dst_row, src_row) in dst.vals.iter_mut().zip(src.vals.iter()) {
+        *dst_row = *dst_row + *src_row * scale;
+    }
+}
+
+// Same as norm_1 of mat - I without building it
+fn norm_1_from_identity<const N: usize>(mat: &Mat<N, N>) -> f32 {
+    let mut answer: f32 = 0.0;
+    for j in 0..N {
+        let mut sum = 0.0;
+        for i in 0..N {
+            let identity = if i == j { 1.0 } else { 0.0 };
+            sum += (mat[i][j] - identity).abs();
+        }
+        answer = answer.max(sum);
+    }
+    answer
+}
+
+// mat is copied to work so it survives, inv ends up as its inverse
+fn inverse_into<const N: usize>(mat: &Mat<N, N>, inv: &mut Mat<N, N>, work: &mut Mat<N, N>) -> Result<(), MatrixError> {
+    work.vals.copy_from_slice(&mat.vals);
+    set_identity(inv);
+    invert_in_place(work, inv)
+}
+
+// coeffs needs room for N + 1 values
+#[cfg(feature = "alloc")]
+pub(crate) fn characteristic_polynomial_in_place<const N: usize>(mat: &Mat<N, N>, coeffs: &mut [f32], work: [&mut Mat<N, N>; 2]) {
+    let [mut m, mut next] = work;
+    coeffs[N] = 1.0;
+    for row in m.vals.iter_mut() {
+        *row = VecN::new();
+    }
+    for k in 1..=N {
+        // M_k = A * M_(k-1) + c_(n-k+1) * I
+        gemm(1.0, mat, m, 0.0, next);
+        core::mem::swap(&mut m, &mut next);
+        for i in 0..N {
+            m[i][i] += coeffs[N - k + 1];
+        }
+        gemm(1.0, mat, m, 0.0, next);
+        coeffs[N - k] = -next.trace() / k as f32;
+    }
+}
+
+pub(crate) fn exp_in_place<const N: usize>(mat: &Mat<N, N>, answer: &mut Mat<N, N>, work: [&mut Mat<N, N>; 4]) -> Result<(), MatrixError> {
+    const Q: usize = 6;
+    let [scaled, denom, mut power, mut next] = work;
+
+    // norm_1 uses max, which skips NaN, so check up front
+    if !mat.is_finite() {
+        return Err(MatrixError::NonFinite);
+    }
+    let norm = mat.norm_1();
+    let squarings = if norm > 0.5 {
+        (norm / 0.5).log2().ceil() as i32
+    } else {
+        0
+    };
+    let divisor = 2f32.powi(squarings);
+    for (row, mat_row) in scaled.vals.iter_mut().zip(mat.vals.iter()) {
+        *row = *mat_row / divisor;
+    }
+
+    // answer holds the numerator until the end
+    set_identity(answer);
+    set_identity(denom);
+    set_identity(power);
+    let mut coeff = 1.0;
+    for k in 1..=Q {
+        coeff *= (Q - k + 1) as f32 / (k * (2 * Q - k + 1)) as f32;
+        gemm(1.0, power, scaled, 0.0, next);
+        core::mem::swap(&mut power, &mut next);
+        add_scaled(answer, power, coeff);
+        add_scaled(denom, power, if k % 2 == 1 { -coeff } else { coeff });
+    }
+
+    // denom is always invertible once the norm has been scaled down
+    set_identity(power);
+    invert_in_place(denom, power)?;
+    gemm(1.0, power, answer, 0.0, next);
+    for _ in 0..squarings {
+        gemm(1.0, next, next, 0.0, power);
+        core::mem::swap(&mut power, &mut next);
+    }
+    answer.vals.copy_from_slice(&next.vals);
+    Ok(())
+}
+
+pub(crate) fn sqrt_in_place<const N: usize>(mat: &Mat<N, N>, answer: &mut Mat<N, N>, work: [&mut Mat<N, N>; 4]) -> Result<(), MatrixError> {
+    let [z, y_inv, z_inv, mut next] = work;
+    let mut y = answer;
+    y.vals.copy_from_slice(&mat.vals);
+    set_identity(z);
+
+    let mut residual = f32::INFINITY;
+    for iteration in 0..MAX_ITERATIONS {
+        inverse_into(y, y_inv, next)?;
+        inverse_into(z, z_inv, next)?;
+        for i in 0..N {
+            next[i] = (y[i] + z_inv[i]) / 2.0;
+            // z_inv is done with, it holds the change in y for the residual
+            z_inv[i] = next[i] - y[i];
+            z[i] = (z[i] + y_inv[i]) / 2.0;
+        }
+
+        residual = z_inv.norm_1();
+        core::mem::swap(&mut y, &mut next);
+        if residual <= 1e-6 * y.norm_1() {
+            // Every other iteration the answer is sitting in a work buffer
+            if iteration % 2 == 0 {
+                next.vals.copy_from_slice(&y.vals);
+            }
+            return Ok(());
+        }
+    }
+    Err(MatrixError::NotConverged {iterations: MAX_ITERATIONS, residual})
+}
+
+pub(crate) fn ln_in_place<const N: usize>(mat: &Mat<N, N>, answer: &mut Mat<N, N>, work: [&mut Mat<N, N>; 5]) -> Result<(), MatrixError> {
+    let [root, a, b, c, d] = work;
+    root.vals.copy_from_slice(&mat.vals);
+    let mut roots = 0;
+    loop {
+        let residual = norm_1_from_identity(root);
+        if residual <= 0.25 {
+            break;
+        }
+        if roots >= MAX_ITERATIONS {
+            return Err(MatrixError::NotConverged {iterations: roots, residual});
+        }
+        sqrt_in_place(root, answer, [&mut *a, &mut *b, &mut *c, &mut *d])?;
+        root.vals.copy_from_slice(&answer.vals);
+        roots += 1;
+    }
+
+    // log(A) = 2 * (Z + Z^3/3 + Z^5/5 + ...) with Z = (A - I)(A + I)^-1
+    b.vals.copy_from_slice(&root.vals);
+    for i in 0..N {
+        b[i][i] += 1.0;
+        root[i][i] -= 1.0;
+    }
+    set_identity(a);
+    invert_in_place(b, a)?;
+    let (mut power, z_sq, mut next) = (b, c, d);
+    gemm(1.0, root, a, 0.0, power);
+    gemm(1.0, power, power, 0.0, z_sq);
+
+    for row in answer.vals.iter_mut() {
+        *row = VecN::new();
+    }
+    for k in 0..8 {
+        add_scaled(answer, power, 1.0 / (2 * k + 1) as f32);
+        gemm(1.0, power, z_sq, 0.0, next);
+        core::mem::swap(&mut power, &mut next);
+    }
+    let scale = 2f32.powi(roots as i32 + 1);
+    for row in answer.vals.iter_mut() {
+        *row = *row * scale;
+    }
+    Ok(())
+}
+
+// Sizes of the column and inner dimension blocks in gemm, a GEMM_BLOCK_K x GEMM_BLOCK_J
+// block of b is 32KB so it stays in cache while every row of a is run against it
+const GEMM_BLOCK_K: usize = 64;
+const GEMM_BLOCK_J: usize = 128;
+// Width of the block of c that gemm_tile keeps in registers, 4 rows by 8 columns
+const GEMM_TILE_J: usize = 8;
+
+// c = alpha * a * b + beta * c, like BLAS sgemm. With beta = 0 the old values in c are
+// ignored, even NaN. Each value of c starts at beta * c and has alpha * a[i][k] * b[k][j]
+// added to it in order of k, so the result does not depend on the block sizes.
+pub fn gemm<const N: usize, const M: usize, const Z: usize>(alpha: f32, a: &Mat<N, M>, b: &Mat<M, Z>, beta: f32, c: &mut Mat<N, Z>) {
+    for row in c.vals.iter_mut() {
+        for val in row.vals.iter_mut() {
+            *val = if beta == 0.0 { 0.0 } else { *val * beta };
+        }
+    }
+    if alpha == 0.0 {
+        return;
+    }
+
+    for k_start in (0..M).step_by(GEMM_BLOCK_K) {
+        let k_end = (k_start + GEMM_BLOCK_K).min(M);
+        for j_start in (0..Z).step_by(GEMM_BLOCK_J) {
+            let j_end = (j_start + GEMM_BLOCK_J).min(Z);
+            for (c_rows, a_rows) in c.vals.chunks_mut(4).zip(a.vals.chunks(4)) {
+                let mut j = j_start;
+                if c_rows.len() == 4 {
+                    while j + GEMM_TILE_J <= j_end {
+                        gemm_tile(alpha, a_rows, b, c_rows, k_start..k_end, j);
+                        j += GEMM_TILE_J;
+                    }
+                }
+                // Whatever is left at the right and bottom edges that does not fill a tile
+                for (c_row, a_row) in c_rows.iter_mut().zip(a_rows) {
+                    let c_row = &mut c_row.vals[j..j_end];
+                    for (a_val, b_row) in a_row.vals[k_start..k_end].iter().zip(&b.vals[k_start..k_end]) {
+                        let weight = alpha * a_val;
+                        for (val, b_val) in c_row.iter_mut().zip(&b_row.vals[j..j_end]) {
+                            *val += weight * b_val;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Accumulates a 4 x GEMM_TILE_J block of c in locals over the whole k range, so it is
+// only loaded and stored once and each value of b is used for all four rows
+fn gemm_tile<const M: usize, const Z: usize>(alpha: f32, a_rows: &[VecN<M>], b: &Mat<M, Z>, c_rows: &mut [VecN<Z>], k_range: Range<usize>, j: usize) {
+    let mut acc = [[0.0; GEMM_TILE_J]; 4];
+    for (acc_row, c_row) in acc.iter_mut().zip(c_rows.iter()) {
+        acc_row.copy_from_slice(&c_row.vals[j..j + GEMM_TILE_J]);
+    }
+    for (k, b_row) in k_range.clone().zip(&b.vals[k_range]) {
+        let b_tile = &b_row.vals[j..j + GEMM_TILE_J];
+        for (acc_row, a_row) in acc.iter_mut().zip(a_rows) {
+            let weight = alpha * a_row.vals[k];
+            for (val, b_val) in acc_row.iter_mut().zip(b_tile) {
+                *val += weight * b_val;
+            }
+        }
+    }
+    for (acc_row, c_row) in acc.iter().zip(c_rows.iter_mut()) {
+        c_row.vals[j..j + GEMM_TILE_J].copy_from_slice(acc_row);
+    }
+}
+
+#[cfg(feature = "alloc")]
 pub fn determinent(vals: Vec<Vec<f32>>) -> f32 {
     if vals.len() == 1 {
         return vals[0][0];
@@ -0,0 +1,173 @@
+use core::ops::{Add,Sub,Index,IndexMut};
+use crate::vectors::VecN;
+use crate::errors::MatrixError;
+
+// A position in space. Points can be offset by a VecN and subtracted to get the VecN between them,
+// but adding two points together makes no sense so it is not allowed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point<const N: usize> {
+    pub vals: VecN<N>,
+}
+
+impl<const N: usize> Point<N> {
+    pub fn origin() -> Point<N> {
+        Point {vals: VecN::new()}
+    }
+
+    pub fn to_vec(&self) -> VecN<N> {
+        self.vals
+    }
+
+    pub fn vec_to(&self, other: &Point<N>) -> VecN<N> {
+        other - self
+    }
+
+    pub fn dist(&self, other: &Point<N>) -> f32 {
+        self.vec_to(other).length()
+    }
+
+    // None for an empty slice
+    pub fn centroid(points: &[Point<N>]) -> Option<Point<N>> {
+        if points.is_empty() {
+            return None;
+        }
+        let mut sum = VecN::<N>::new();
+        for point in points {
+            sum = sum + point.vals;
+        }
+        Some(Point {vals: sum / points.len() as f32})
+    }
+
+    // Weighted sum of points, only meaningful when the weights add up to one.
+    // Fails if the lengths differ, the slices are empty or the weights do not sum to one.
+    pub fn affine_combination(points: &[Point<N>], weights: &[f32]) -> Result<Point<N>, MatrixError> {
+        if points.is_empty() {
+            return Err(MatrixError::TooFewPoints {needed: 1, found: 0});
+        }
+        if points.len() != weights.len() {
+            return Err(MatrixError::DimensionMismatch {expected: points.len(), found: weights.len()});
+        }
+        let total: f32 = weights.iter().sum();
+        if (total - 1.0).abs() > 1e-5 {
+            return Err(MatrixError::WeightSum {sum: total});
+        }
+
+        // Offsets from the first point so the result does not depend on where the origin is
+        let base = points[0];
+        let mut answer = base;
+        for (point, weight) in points.iter().zip(weights).skip(1) {
+            answer = answer + (point - base) * *weight;
+        }
+        Ok(answer)
+    }
+}
+
+impl<const N: usize> Sub<Point<N>> for Point<N> {
+    type Output = VecN<N>;
+
+    fn sub(self, other: Point<N>) -> VecN<N> {
+        self.vals - other.vals
+    }
+}
+impl<const N: usize> Sub<&Point<N>> for &Point<N> {
+    type Output = VecN<N>;
+    fn sub(self, other: &Point<N>) -> VecN<N> {
+        *self - *other
+    }
+}
+impl<const N: usize> Sub<Point<N>> for &Point<N> {
+    type Output = VecN<N>;
+    fn sub(self, other: Point<N>) -> VecN<N> {
+        *self - other
+    }
+}
+impl<const N: usize> Sub<&Point<N>> for Point<N> {
+    type Output = VecN<N>;
+    fn sub(self, other: &Point<N>) -> VecN<N> {
+        self - *other
+    }
+}
+
+impl<const N: usize> Add<VecN<N>> for Point<N> {
+    type Output = Point<N>;
+
+    fn add(self, other: VecN<N>) -> Point<N> {
+        Point {vals: self.vals + other}
+    }
+}
+impl<const N: usize> Add<&VecN<N>> for &Point<N> {
+    type Output = Point<N>;
+    fn add(self, other: &VecN<N>) -> Point<N> {
+        *self + *other
+    }
+}
+impl<const N: usize> Add<VecN<N>> for &Point<N> {
+    type Output = Point<N>;
+    fn add(self, other: VecN<N>) -> Point<N> {
+        *self + other
+    }
+}
+impl<const N: usize> Add<&VecN<N>> for Point<N> {
+    type Output = Point<N>;
+    fn add(self, other: &VecN<N>) -> Point<N> {
+        self + *other
+    }
+}
+
+impl<const N: usize> Sub<VecN<N>> for Point<N> {
+    type Output = Point<N>;
+
+    fn sub(self, other: VecN<N>) -> Point<N> {
+        Point {vals: self.vals - other}
+    }
+}
+impl<const N: usize> Sub<&VecN<N>> for &Point<N> {
+    type Output = Point<N>;
+    fn sub(self, other: &VecN<N>) -> Point<N> {
+        *self - *other
+    }
+}
+impl<const N: usize> Sub<VecN<N>> for &Point<N> {
+    type Output = Point<N>;
+    fn sub(self, other: VecN<N>) -> Point<N> {
+        *self - other
+    }
+}
+impl<const N: usize> Sub<&VecN<N>> for Point<N> {
+    type Output = Point<N>;
+    fn sub(self, other: &VecN<N>) -> Point<N> {
+        self - *other
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &f32 {
+        &self.vals[index]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        &mut self.vals[index]
+    }
+}
+
+impl<const N: usize> From<VecN<N>> for Point<N> {
+    fn from(vals: VecN<N>) -> Point<N> {
+        Point {vals}
+    }
+}
+
+impl<const N: usize> From<Point<N>> for VecN<N> {
+    fn from(point: Point<N>) -> VecN<N> {
+        point.vals
+    }
+}
+
+impl<const N: usize> From<[f32; N]> for Point<N> {
+    fn from(vals: [f32; N]) -> Point<N> {
+        Point {vals: VecN::from(vals)}
+    }
+}
@@ -0,0 +1,187 @@
+use crate::vectors::VecN;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+// Twice the signed area of the triangle abc, positive if it turns counter clockwise,
+// negative if clockwise and zero if collinear. Done and returned in f64 so the sign is
+// right for everything short of extremely close to collinear inputs, tiny triangles
+// would round to 0 as an f32.
+pub fn orient2d(a: &VecN<2>, b: &VecN<2>, c: &VecN<2>) -> f64 {
+    let (ax, ay) = (a[0] as f64, a[1] as f64);
+    let (bx, by) = (b[0] as f64, b[1] as f64);
+    let (cx, cy) = (c[0] as f64, c[1] as f64);
+    (bx - ax) * (cy - ay) - (by - ay) * (cx - ax)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SegmentIntersection {
+    None,
+    Point(VecN<2>),
+    // Collinear segments that share more than one point, given as the ends of the shared part
+    Overlap(VecN<2>, VecN<2>),
+}
+
+pub fn segment_intersection(a1: &VecN<2>, a2: &VecN<2>, b1: &VecN<2>, b2: &VecN<2>) -> SegmentIntersection {
+    let d1 = orient2d(b1, b2, a1);
+    let d2 = orient2d(b1, b2, a2);
+    let d3 = orient2d(a1, a2, b1);
+    let d4 = orient2d(a1, a2, b2);
+
+    // A single point b gives d1 = d2 = 0 wherever it is, so d3 and d4 have to agree too
+    if d1 == 0.0 && d2 == 0.0 && d3 == 0.0 && d4 == 0.0 {
+        return collinear_overlap(a1, a2, b1, b2);
+    }
+    if d1 * d2 > 0.0 || d3 * d4 > 0.0 {
+        return SegmentIntersection::None;
+    }
+
+    let t = (d1 / (d1 - d2)) as f32;
+    SegmentIntersection::Point(a1 + (a2 - a1) * t)
+}
+
+fn collinear_overlap(a1: &VecN<2>, a2: &VecN<2>, b1: &VecN<2>, b2: &VecN<2>) -> SegmentIntersection {
+    let dir = a2 - a1;
+    let length_sq = dir * dir;
+    if length_sq == 0.0 {
+        // a is a single point
+        let other = b2 - b1;
+        let t = if other * other == 0.0 { 0.0 } else { (a1 - b1) * other / (other * other) };
+        if (0.0..=1.0).contains(&t) && (b1 + other * t).dist(a1) == 0.0 {
+            return SegmentIntersection::Point(*a1);
+        }
+        return SegmentIntersection::None;
+    }
+
+    // Position of b along a with a1 at 0 and a2 at 1
+    let t1 = (b1 - a1) * dir / length_sq;
+    let t2 = (b2 - a1) * dir / length_sq;
+    let start = t1.min(t2).max(0.0);
+    let end = t1.max(t2).min(1.0);
+    if start > end {
+        SegmentIntersection::None
+    } else if start == end {
+        SegmentIntersection::Point(a1 + dir * start)
+    } else {
+        SegmentIntersection::Overlap(a1 + dir * start, a1 + dir * end)
+    }
+}
+
+// Even-odd rule, points exactly on an edge can land on either side
+pub fn point_in_polygon(point: &VecN<2>, polygon: &[VecN<2>]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (a, b) = (&polygon[i], &polygon[j]);
+        if (a[1] > point[1]) != (b[1] > point[1]) {
+            let x = a[0] + (point[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+            if point[0] < x {
+                inside = !inside;
+            }
+        }
+        j = i;
+    }
+    inside
+}
+
+// Shoelace formula, positive for counter clockwise polygons
+pub fn signed_area(polygon: &[VecN<2>]) -> f32 {
+    let mut answer = 0.0;
+    for i in 0..polygon.len() {
+        let next = &polygon[(i + 1) % polygon.len()];
+        answer += polygon[i].perp_dot(next);
+    }
+    answer / 2.0
+}
+
+// Centroid of the enclosed area, None if the polygon has no area
+pub fn polygon_centroid(polygon: &[VecN<2>]) -> Option<VecN<2>> {
+    let area = signed_area(polygon);
+    if area == 0.0 {
+        return None;
+    }
+
+    let mut answer = VecN::<2>::new();
+    for i in 0..polygon.len() {
+        let (a, b) = (&polygon[i], &polygon[(i + 1) % polygon.len()]);
+        answer = answer + (a + b) * a.perp_dot(b);
+    }
+    Some(answer / (6.0 * area))
+}
+
+// Andrew's monotone chain, the hull is counter clockwise and skips collinear points
+#[cfg(feature = "alloc")]
+pub fn convex_hull(points: &[VecN<2>]) -> Vec<VecN<2>> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    sorted.dedup_by(|a, b| a[0] == b[0] && a[1] == b[1]);
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let mut hull: Vec<VecN<2>> = Vec::with_capacity(sorted.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        for point in sorted.iter() {
+            while hull.len() >= start + 2 && orient2d(&hull[hull.len() - 2], &hull[hull.len() - 1], point) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(*point);
+        }
+        // The last point of each chain is the first point of the other
+        hull.pop();
+        if pass == 0 {
+            sorted.reverse();
+        }
+    }
+    hull
+}
+
+// Ear clipping for simple polygons in either winding. Returns indices into the polygon
+// for each triangle, counter clockwise. None if there are fewer than 3 points or no ear
+// can be found, which happens for self intersecting polygons.
+#[cfg(feature = "alloc")]
+pub fn triangulate(polygon: &[VecN<2>]) -> Option<Vec<[usize; 3]>> {
+    if polygon.len() < 3 {
+        return None;
+    }
+
+    // Same sign as signed_area but in f64 like orient2d, so tiny polygons keep their winding
+    let twice_area: f64 = (1..polygon.len() - 1)
+        .map(|i| orient2d(&polygon[0], &polygon[i], &polygon[i + 1]))
+        .sum();
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    if twice_area < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut triangles = Vec::with_capacity(polygon.len() - 2);
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&i| {
+            let prev = remaining[(i + count - 1) % count];
+            let next = remaining[(i + 1) % count];
+            is_ear(polygon, &remaining, prev, remaining[i], next)
+        })?;
+        let prev = remaining[(ear + count - 1) % count];
+        let next = remaining[(ear + 1) % count];
+        triangles.push([prev, remaining[ear], next]);
+        remaining.remove(ear);
+    }
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    Some(triangles)
+}
+
+#[cfg(feature = "alloc")]
+fn is_ear(polygon: &[VecN<2>], remaining: &[usize], prev: usize, at: usize, next: usize) -> bool {
+    let (a, b, c) = (&polygon[prev], &polygon[at], &polygon[next]);
+    if orient2d(a, b, c) <= 0.0 {
+        return false;
+    }
+    // No other vertex can be inside or on the triangle
+    remaining.iter()
+        .filter(|&&i| i != prev && i != at && i != next)
+        .all(|&i| {
+            let p = &polygon[i];
+            orient2d(a, b, p) < 0.0 || orient2d(b, c, p) < 0.0 || orient2d(c, a, p) < 0.0
+        })
+}
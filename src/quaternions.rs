@@ -0,0 +1,221 @@
+use core::ops::Mul;
+use crate::vectors::{VecN, Unit};
+use crate::matrixs::Mat;
+use crate::errors::MatrixError;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+
+// Stored as [x, y, z, w] where w is the real part
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quat {
+    pub vals: VecN<4>,
+}
+
+impl Quat {
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Quat {
+        Quat {vals: VecN::from([x, y, z, w])}
+    }
+
+    pub fn identity() -> Quat {
+        Quat::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    // Same as Mat::rotation_axis_angle, no rotation at all for a zero axis
+    pub fn from_axis_angle(axis: &VecN<3>, theta: f32) -> Quat {
+        match Unit::try_new(*axis, 0.0) {
+            Some(axis) => Quat::from_unit_axis_angle(&axis, theta),
+            None => Quat::identity(),
+        }
+    }
+
+    pub fn from_unit_axis_angle(axis: &Unit<VecN<3>>, theta: f32) -> Quat {
+        let axis = **axis * f32::sin(theta / 2.0);
+        Quat::new(axis[0], axis[1], axis[2], f32::cos(theta / 2.0))
+    }
+
+    // Shortest rotation that takes the direction of from onto the direction of to
+    pub fn from_two_vectors(from: &VecN<3>, to: &VecN<3>) -> Quat {
+        let from = from.normalize();
+        let to = to.normalize();
+        let dot = from * to;
+
+        if dot < -1.0 + 1e-6 {
+            // Opposite vectors, any perpendicular axis works for a half turn
+            let mut axis = VecN::from([1.0, 0.0, 0.0]).cross(&from);
+            if axis.length() < 1e-6 {
+                axis = VecN::from([0.0, 1.0, 0.0]).cross(&from);
+            }
+            return Quat::from_axis_angle(&axis, core::f32::consts::PI);
+        }
+
+        let axis = from.cross(&to);
+        Quat::new(axis[0], axis[1], axis[2], 1.0 + dot).normalize()
+    }
+
+    pub fn w(&self) -> f32 {
+        self.vals[3]
+    }
+
+    pub fn vector(&self) -> VecN<3> {
+        VecN::from([self.vals[0], self.vals[1], self.vals[2]])
+    }
+
+    pub fn dot(&self, other: &Quat) -> f32 {
+        self.vals * other.vals
+    }
+
+    pub fn length(&self) -> f32 {
+        self.vals.length()
+    }
+
+    pub fn normalize(&self) -> Quat {
+        Quat {vals: self.vals.normalize()}
+    }
+
+    pub fn conjugate(&self) -> Quat {
+        Quat::new(-self.vals[0], -self.vals[1], -self.vals[2], self.vals[3])
+    }
+
+    // The zero quaternion has no inverse
+    pub fn inverse(&self) -> Result<Quat, MatrixError> {
+        let length_sq = self.dot(self);
+        if length_sq == 0.0 {
+            return Err(MatrixError::Singular);
+        }
+        Ok(Quat {vals: self.conjugate().vals / length_sq})
+    }
+
+    // Assumes a unit quaternion
+    pub fn rotate(&self, vector: &VecN<3>) -> VecN<3> {
+        let q = self.vector();
+        let t = q.cross(vector) * 2.0;
+        vector + t * self.w() + q.cross(&t)
+    }
+
+    pub fn axis_angle(&self) -> (VecN<3>, f32) {
+        let q = self.normalize();
+        let sin = q.vector().length();
+        if sin < 1e-6 {
+            return (VecN::from([1.0, 0.0, 0.0]), 0.0);
+        }
+        (q.vector() / sin, 2.0 * f32::atan2(sin, q.w()))
+    }
+
+    pub fn nlerp(&self, other: &Quat, t: f32) -> Quat {
+        let other = if self.dot(other) < 0.0 { -1.0 * other.vals } else { other.vals };
+        Quat {vals: self.vals * (1.0 - t) + other * t}.normalize()
+    }
+
+    // Takes the short way around, falls back to nlerp when the rotations are nearly equal
+    pub fn slerp(&self, other: &Quat, t: f32) -> Quat {
+        let mut dot = self.dot(other);
+        let mut other = other.vals;
+        if dot < 0.0 {
+            dot = -dot;
+            other = -1.0 * other;
+        }
+        if dot > 0.9995 {
+            return self.nlerp(&Quat {vals: other}, t);
+        }
+
+        let theta = f32::acos(dot);
+        let sin = f32::sin(theta);
+        let a = f32::sin((1.0 - t) * theta) / sin;
+        let b = f32::sin(t * theta) / sin;
+        Quat {vals: self.vals * a + other * b}
+    }
+}
+
+// Hamilton product
+impl Mul<&Quat> for &Quat {
+    type Output = Quat;
+
+    fn mul(self, other: &Quat) -> Quat {
+        let (x1, y1, z1, w1) = (self.vals[0], self.vals[1], self.vals[2], self.vals[3]);
+        let (x2, y2, z2, w2) = (other.vals[0], other.vals[1], other.vals[2], other.vals[3]);
+        Quat::new(w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2,
+                  w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2,
+                  w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+                  w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2)
+    }
+}
+impl Mul<Quat> for &Quat {
+    type Output = Quat;
+    fn mul(self, other: Quat) -> Quat {
+        *self * other
+    }
+}
+impl Mul<&Quat> for Quat {
+    type Output = Quat;
+    fn mul(self, other: &Quat) -> Quat {
+        self * *other
+    }
+}
+impl Mul<Quat> for Quat {
+    type Output = Quat;
+    fn mul(self, other: Quat) -> Quat {
+        &self * &other
+    }
+}
+
+impl From<VecN<4>> for Quat {
+    fn from(vals: VecN<4>) -> Quat {
+        Quat {vals}
+    }
+}
+
+impl From<[f32; 4]> for Quat {
+    fn from(vals: [f32; 4]) -> Quat {
+        Quat {vals: VecN::from(vals)}
+    }
+}
+
+impl From<&Quat> for Mat<3,3> {
+    fn from(quat: &Quat) -> Mat<3,3> {
+        let q = quat.normalize();
+        let (x, y, z, w) = (q.vals[0], q.vals[1], q.vals[2], q.vals[3]);
+        Mat::from([[1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w), 2.0 * (x * z + y * w)],
+                    [2.0 * (x * y + z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w)],
+                    [2.0 * (x * z - y * w), 2.0 * (y * z + x * w), 1.0 - 2.0 * (x * x + y * y)]])
+    }
+}
+
+impl From<&Quat> for Mat<4,4> {
+    fn from(quat: &Quat) -> Mat<4,4> {
+        let rot = Mat::<3,3>::from(quat);
+        let mut mat = Mat::<4,4>::identity();
+        for i in 0..3 {
+            for j in 0..3 {
+                mat[i][j] = rot[i][j];
+            }
+        }
+        mat
+    }
+}
+
+// Expects a pure rotation matrix, picks the largest diagonal term to stay stable
+impl From<&Mat<3,3>> for Quat {
+    fn from(m: &Mat<3,3>) -> Quat {
+        let trace = m.trace();
+        let quat = if trace > 0.0 {
+            let s = f32::sqrt(trace + 1.0) * 2.0;
+            Quat::new((m[2][1] - m[1][2]) / s, (m[0][2] - m[2][0]) / s, (m[1][0] - m[0][1]) / s, s / 4.0)
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = f32::sqrt(1.0 + m[0][0] - m[1][1] - m[2][2]) * 2.0;
+            Quat::new(s / 4.0, (m[0][1] + m[1][0]) / s, (m[0][2] + m[2][0]) / s, (m[2][1] - m[1][2]) / s)
+        } else if m[1][1] > m[2][2] {
+            let s = f32::sqrt(1.0 + m[1][1] - m[0][0] - m[2][2]) * 2.0;
+            Quat::new((m[0][1] + m[1][0]) / s, s / 4.0, (m[1][2] + m[2][1]) / s, (m[0][2] - m[2][0]) / s)
+        } else {
+            let s = f32::sqrt(1.0 + m[2][2] - m[0][0] - m[1][1]) * 2.0;
+            Quat::new((m[0][2] + m[2][0]) / s, (m[1][2] + m[2][1]) / s, s / 4.0, (m[1][0] - m[0][1]) / s)
+        };
+        quat.normalize()
+    }
+}
+
+impl From<&Mat<4,4>> for Quat {
+    fn from(m: &Mat<4,4>) -> Quat {
+        Quat::from(&m.linear_part())
+    }
+}
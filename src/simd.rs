@@ -0,0 +1,289 @@
+// Kernels behind the VecN and Mat arithmetic. On x86_64 with the simd feature SSE2 is
+// always used (it is part of the baseline) and AVX2 is picked at runtime for longer
+// vectors (at compile time without std). Everywhere else the plain scalar loops are used.
+//
+// By default every kernel gives the same bits as the scalar loops: sums are done in the
+// same order and there is no fused multiply-add. The fast-math feature drops that
+// guarantee for dot products so they can be split across lanes and use FMA.
+
+pub(crate) type Mat4Rows = [[f32; 4]; 4];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Scalar,
+    Sse2,
+    Avx2,
+}
+
+// The widest instruction set the kernels will use on this machine
+pub fn backend() -> Backend {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        if x86::has_avx2() { Backend::Avx2 } else { Backend::Sse2 }
+    }
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    {
+        Backend::Scalar
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+pub(crate) use x86::{add, sub, scale, dot, mat4_mul, mat4_vec, transpose4};
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+pub(crate) use scalar::{add, sub, scale, dot, mat4_mul, mat4_vec, transpose4};
+
+// Also handles the leftover elements that do not fill a whole register
+#[cfg_attr(all(feature = "simd", target_arch = "x86_64"), allow(dead_code))]
+mod scalar {
+    use super::Mat4Rows;
+
+    pub fn add(a: &[f32], b: &[f32], out: &mut [f32]) {
+        for ((out, a), b) in out.iter_mut().zip(a).zip(b) {
+            *out = a + b;
+        }
+    }
+
+    pub fn sub(a: &[f32], b: &[f32], out: &mut [f32]) {
+        for ((out, a), b) in out.iter_mut().zip(a).zip(b) {
+            *out = a - b;
+        }
+    }
+
+    pub fn scale(a: &[f32], scalar: f32, out: &mut [f32]) {
+        for (out, a) in out.iter_mut().zip(a) {
+            *out = a * scalar;
+        }
+    }
+
+    pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+        let mut answer = 0.0;
+        for (a, b) in a.iter().zip(b) {
+            answer += a * b;
+        }
+        answer
+    }
+
+    pub fn mat4_mul(a: &Mat4Rows, b: &Mat4Rows) -> Mat4Rows {
+        let mut answer = [[0.0; 4]; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                for k in 0..4 {
+                    answer[i][j] += a[i][k] * b[k][j];
+                }
+            }
+        }
+        answer
+    }
+
+    pub fn mat4_vec(a: &Mat4Rows, v: &[f32; 4]) -> [f32; 4] {
+        let mut answer = [0.0; 4];
+        for i in 0..4 {
+            answer[i] = dot(&a[i], v);
+        }
+        answer
+    }
+
+    pub fn transpose4(a: &Mat4Rows) -> Mat4Rows {
+        let mut answer = [[0.0; 4]; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                answer[j][i] = a[i][j];
+            }
+        }
+        answer
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use core::arch::x86_64::*;
+    use super::{scalar, Mat4Rows};
+
+    // Runtime detection needs std, without it only what the target was built with is used
+    #[cfg(feature = "std")]
+    pub fn has_avx2() -> bool {
+        is_x86_feature_detected!("avx2")
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn has_avx2() -> bool {
+        cfg!(target_feature = "avx2")
+    }
+
+    #[cfg(all(feature = "fast-math", feature = "std"))]
+    fn has_fma() -> bool {
+        is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")
+    }
+
+    #[cfg(all(feature = "fast-math", not(feature = "std")))]
+    fn has_fma() -> bool {
+        cfg!(all(target_feature = "avx2", target_feature = "fma"))
+    }
+
+    // a op b into out, 8 at a time with AVX2, then 4 at a time with SSE2, then the rest
+    macro_rules! elementwise {
+        ($name:ident, $avx2_name:ident, $sse:ident, $avx:ident) => {
+            pub fn $name(a: &[f32], b: &[f32], out: &mut [f32]) {
+                assert!(a.len() == out.len() && b.len() == out.len());
+                let len = out.len();
+                let mut i = 0;
+                if len >= 8 && has_avx2() {
+                    i = unsafe { $avx2_name(a, b, out) };
+                }
+                while i + 4 <= len {
+                    unsafe {
+                        let answer = $sse(_mm_loadu_ps(a.as_ptr().add(i)), _mm_loadu_ps(b.as_ptr().add(i)));
+                        _mm_storeu_ps(out.as_mut_ptr().add(i), answer);
+                    }
+                    i += 4;
+                }
+                scalar::$name(&a[i..], &b[i..], &mut out[i..]);
+            }
+
+            // Returns how many elements were done
+            #[target_feature(enable = "avx2")]
+            unsafe fn $avx2_name(a: &[f32], b: &[f32], out: &mut [f32]) -> usize {
+                let mut i = 0;
+                while i + 8 <= out.len() {
+                    let answer = $avx(_mm256_loadu_ps(a.as_ptr().add(i)), _mm256_loadu_ps(b.as_ptr().add(i)));
+                    _mm256_storeu_ps(out.as_mut_ptr().add(i), answer);
+                    i += 8;
+                }
+                i
+            }
+        };
+    }
+
+    elementwise!(add, add_avx2, _mm_add_ps, _mm256_add_ps);
+    elementwise!(sub, sub_avx2, _mm_sub_ps, _mm256_sub_ps);
+
+    pub fn scale(a: &[f32], scalar: f32, out: &mut [f32]) {
+        assert!(a.len() == out.len());
+        let len = out.len();
+        let mut i = 0;
+        if len >= 8 && has_avx2() {
+            i = unsafe { scale_avx2(a, scalar, out) };
+        }
+        while i + 4 <= len {
+            unsafe {
+                let answer = _mm_mul_ps(_mm_loadu_ps(a.as_ptr().add(i)), _mm_set1_ps(scalar));
+                _mm_storeu_ps(out.as_mut_ptr().add(i), answer);
+            }
+            i += 4;
+        }
+        scalar::scale(&a[i..], scalar, &mut out[i..]);
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn scale_avx2(a: &[f32], scalar: f32, out: &mut [f32]) -> usize {
+        let factor = _mm256_set1_ps(scalar);
+        let mut i = 0;
+        while i + 8 <= out.len() {
+            _mm256_storeu_ps(out.as_mut_ptr().add(i), _mm256_mul_ps(_mm256_loadu_ps(a.as_ptr().add(i)), factor));
+            i += 8;
+        }
+        i
+    }
+
+    // Splitting the sum across lanes changes the rounding, so this is only vectorized with fast-math
+    #[cfg(not(feature = "fast-math"))]
+    pub use super::scalar::dot;
+
+    #[cfg(feature = "fast-math")]
+    pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+        assert!(a.len() == b.len());
+        let len = a.len();
+        let mut i = 0;
+        let mut answer = 0.0;
+        if len >= 8 && has_fma() {
+            let (done, sum) = unsafe { dot_fma(a, b) };
+            i = done;
+            answer = sum;
+        }
+        if i + 4 <= len {
+            unsafe {
+                let mut acc = _mm_setzero_ps();
+                while i + 4 <= len {
+                    acc = _mm_add_ps(acc, _mm_mul_ps(_mm_loadu_ps(a.as_ptr().add(i)), _mm_loadu_ps(b.as_ptr().add(i))));
+                    i += 4;
+                }
+                answer += horizontal_sum(acc);
+            }
+        }
+        answer + scalar::dot(&a[i..], &b[i..])
+    }
+
+    #[cfg(feature = "fast-math")]
+    #[target_feature(enable = "avx2,fma")]
+    unsafe fn dot_fma(a: &[f32], b: &[f32]) -> (usize, f32) {
+        let mut acc = _mm256_setzero_ps();
+        let mut i = 0;
+        while i + 8 <= a.len() {
+            acc = _mm256_fmadd_ps(_mm256_loadu_ps(a.as_ptr().add(i)), _mm256_loadu_ps(b.as_ptr().add(i)), acc);
+            i += 8;
+        }
+        let sum = _mm_add_ps(_mm256_castps256_ps128(acc), _mm256_extractf128_ps(acc, 1));
+        (i, horizontal_sum(sum))
+    }
+
+    #[cfg(feature = "fast-math")]
+    unsafe fn horizontal_sum(v: __m128) -> f32 {
+        let pairs = _mm_add_ps(v, _mm_movehl_ps(v, v));
+        _mm_cvtss_f32(_mm_add_ss(pairs, _mm_shuffle_ps(pairs, pairs, 1)))
+    }
+
+    unsafe fn load_rows(a: &Mat4Rows) -> [__m128; 4] {
+        [_mm_loadu_ps(a[0].as_ptr()), _mm_loadu_ps(a[1].as_ptr()),
+         _mm_loadu_ps(a[2].as_ptr()), _mm_loadu_ps(a[3].as_ptr())]
+    }
+
+    unsafe fn store_rows(rows: [__m128; 4]) -> Mat4Rows {
+        let mut answer = [[0.0; 4]; 4];
+        for (row, reg) in answer.iter_mut().zip(rows.iter()) {
+            _mm_storeu_ps(row.as_mut_ptr(), *reg);
+        }
+        answer
+    }
+
+    unsafe fn transpose_rows(rows: [__m128; 4]) -> [__m128; 4] {
+        let low01 = _mm_unpacklo_ps(rows[0], rows[1]);
+        let low23 = _mm_unpacklo_ps(rows[2], rows[3]);
+        let high01 = _mm_unpackhi_ps(rows[0], rows[1]);
+        let high23 = _mm_unpackhi_ps(rows[2], rows[3]);
+        [_mm_movelh_ps(low01, low23), _mm_movehl_ps(low23, low01),
+         _mm_movelh_ps(high01, high23), _mm_movehl_ps(high23, high01)]
+    }
+
+    // Each answer row is a sum of the rows of b weighted by a row of a, added in the same
+    // order as the scalar loop
+    pub fn mat4_mul(a: &Mat4Rows, b: &Mat4Rows) -> Mat4Rows {
+        unsafe {
+            let b_rows = load_rows(b);
+            let mut answer = [_mm_setzero_ps(); 4];
+            for (row, a_row) in answer.iter_mut().zip(a.iter()) {
+                for (b_row, &weight) in b_rows.iter().zip(a_row.iter()) {
+                    *row = _mm_add_ps(*row, _mm_mul_ps(_mm_set1_ps(weight), *b_row));
+                }
+            }
+            store_rows(answer)
+        }
+    }
+
+    // Weighted sum of the columns of a, again in the scalar order
+    pub fn mat4_vec(a: &Mat4Rows, v: &[f32; 4]) -> [f32; 4] {
+        unsafe {
+            let columns = transpose_rows(load_rows(a));
+            let mut acc = _mm_setzero_ps();
+            for (column, &weight) in columns.iter().zip(v.iter()) {
+                acc = _mm_add_ps(acc, _mm_mul_ps(*column, _mm_set1_ps(weight)));
+            }
+            let mut answer = [0.0; 4];
+            _mm_storeu_ps(answer.as_mut_ptr(), acc);
+            answer
+        }
+    }
+
+    pub fn transpose4(a: &Mat4Rows) -> Mat4Rows {
+        unsafe { store_rows(transpose_rows(load_rows(a))) }
+    }
+}
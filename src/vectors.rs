@@ -1,23 +1,119 @@
 
-use std::ops::{Add,Sub,Mul,Div,Index,IndexMut,Range};
+use core::ops::{Add,Sub,Mul,Div,Index,IndexMut,Range,RangeFrom,RangeTo,RangeFull,RangeInclusive,RangeToInclusive,Deref};
 use float_cmp::ApproxEq;
-use std::iter::FromIterator;
+use core::iter::FromIterator;
+use core::convert::TryFrom;
+use core::str::FromStr;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use crate::errors::MatrixError;
+use crate::simd;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
 
 #[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
 pub struct VecN<const N: usize> {
     pub vals: [f32; N],
 }
 
+pub type Vec2 = VecN<2>;
+pub type Vec3 = VecN<3>;
+pub type Vec4 = VecN<4>;
+
+// Named getters and setters for each component
+macro_rules! components {
+    ($($get:ident, $set:ident => $i:expr;)*) => {
+        $(
+            pub const fn $get(&self) -> f32 {
+                self.vals[$i]
+            }
+
+            pub const fn $set(&mut self, val: f32) {
+                self.vals[$i] = val;
+            }
+        )*
+    };
+}
+
+// Named swizzles that forward to VecN::swizzle
+macro_rules! swizzles {
+    ($($name:ident: $m:literal => [$($i:expr),+];)*) => {
+        $(
+            pub fn $name(&self) -> VecN<$m> {
+                self.swizzle([$($i),+])
+            }
+        )*
+    };
+}
+
 
 impl<const N: usize> VecN<N> {
-    pub fn new() -> VecN<N> {
+    pub const ZERO: VecN<N> = VecN {vals: [0.0; N]};
+    pub const ONE: VecN<N> = VecN {vals: [1.0; N]};
+
+    pub const fn new() -> VecN<N> {
         VecN{vals: [0.0; N]}
     }
 
+    // Same as From<[f32; N]> but usable in consts and statics
+    pub const fn from_array(vals: [f32; N]) -> VecN<N> {
+        VecN{vals}
+    }
+
     pub fn project(&self, other: &VecN<N>) -> VecN<N> {
         (self * other / (other * other)) * other
     }
 
+    // None if other is not longer than eps
+    pub fn try_project(&self, other: &VecN<N>, eps: f32) -> Option<VecN<N>> {
+        let length_sq = other * other;
+        if length_sq > eps * eps {
+            Some((self * other / length_sq) * other)
+        } else {
+            None
+        }
+    }
+
+    // The part of self perpendicular to other, project and reject add back up to self
+    pub fn reject(&self, other: &VecN<N>) -> VecN<N> {
+        self - self.project(other)
+    }
+
+    pub fn cos_similarity(&self, other: &VecN<N>) -> f32 {
+        self * other / (self.length() * other.length())
+    }
+
+    // Unsigned angle in [0, pi]
+    pub fn angle_between(&self, other: &VecN<N>) -> f32 {
+        f32::acos(self.cos_similarity(other).clamp(-1.0, 1.0))
+    }
+
+    // Tolerance is on the cosine of the angle between the vectors
+    pub fn is_parallel(&self, other: &VecN<N>, tolerance: f32) -> bool {
+        self.cos_similarity(other).abs() >= 1.0 - tolerance
+    }
+
+    pub fn is_orthogonal(&self, other: &VecN<N>, tolerance: f32) -> bool {
+        self.cos_similarity(other).abs() <= tolerance
+    }
+
+    // Mirrors self about the plane with the given unit normal
+    pub fn reflect(&self, normal: &VecN<N>) -> VecN<N> {
+        self - normal * (2.0 * (self * normal))
+    }
+
+    // Snell's law for a unit direction hitting a surface with a unit normal facing against it.
+    // eta is the ratio of indices of refraction (from / to). None on total internal reflection.
+    pub fn refract(&self, normal: &VecN<N>, eta: f32) -> Option<VecN<N>> {
+        let cos_i = -(self * normal);
+        let k = 1.0 - eta * eta * (1.0 - cos_i * cos_i);
+        if k < 0.0 {
+            return None;
+        }
+        Some(self * eta + normal * (eta * cos_i - f32::sqrt(k)))
+    }
+
     pub fn length(&self) -> f32 {
         f32::sqrt(self * self)
     }
@@ -25,13 +121,82 @@ impl<const N: usize> VecN<N> {
     pub fn normalize(&self) -> VecN<N> {
         self / self.length()
     }
+
+    // None if the length is not above eps, so zero vectors do not turn into NaNs
+    pub fn try_normalize(&self, eps: f32) -> Option<VecN<N>> {
+        let length = self.length();
+        if length > eps && length.is_finite() {
+            Some(self / length)
+        } else {
+            None
+        }
+    }
+
+    pub fn normalize_or(&self, fallback: VecN<N>) -> VecN<N> {
+        self.try_normalize(0.0).unwrap_or(fallback)
+    }
+
+    // Modified Gram-Schmidt. A vector is linearly dependent when its length is not
+    // above eps after removing the earlier directions.
+    #[cfg(feature = "alloc")]
+    pub fn orthonormalize(vectors: &[VecN<N>], eps: f32) -> Result<Vec<VecN<N>>, MatrixError> {
+        let mut basis: Vec<VecN<N>> = Vec::with_capacity(vectors.len());
+        for (i, vector) in vectors.iter().enumerate() {
+            let mut v = *vector;
+            for b in basis.iter() {
+                v = v - b * (v * b);
+            }
+            basis.push(v.try_normalize(eps).ok_or(MatrixError::LinearlyDependent {index: i})?);
+        }
+        Ok(basis)
+    }
     
     pub fn dimension() -> usize {
         N
     }
 
+    pub fn is_finite(&self) -> bool {
+        self.vals.iter().all(|val| val.is_finite())
+    }
+
+    // New vector built from the given components in order, e.g. [2, 1, 0] reverses a VecN<3>
+    pub fn swizzle<const M: usize>(&self, indices: [usize; M]) -> VecN<M> {
+        let mut answer = [0.0; M];
+        for (val, &index) in answer.iter_mut().zip(indices.iter()) {
+            *val = self.vals[index];
+        }
+        VecN::from(answer)
+    }
+
     pub fn slice(&self, slice: Range<usize>) -> &[f32] {
-        &self.vals[slice]
+        &self[slice]
+    }
+
+    // None when the index is out of bounds
+    pub const fn get(&self, index: usize) -> Option<&f32> {
+        if index < N {
+            Some(&self.vals[index])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut f32> {
+        self.vals.get_mut(index)
+    }
+
+    /// Skips the bounds check.
+    ///
+    /// # Safety
+    /// index must be less than N
+    pub unsafe fn get_unchecked(&self, index: usize) -> &f32 {
+        self.vals.get_unchecked(index)
+    }
+
+    /// # Safety
+    /// index must be less than N
+    pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut f32 {
+        self.vals.get_unchecked_mut(index)
     }
 
     pub fn dist(&self, other: &VecN<N>) -> f32 {
@@ -42,6 +207,51 @@ impl<const N: usize> VecN<N> {
         other - self
     }
 
+    pub fn lerp(&self, other: &VecN<N>, t: f32) -> VecN<N> {
+        self + (other - self) * t
+    }
+
+    // Constant angular speed between two unit vectors, falls back to a normalized
+    // lerp when they are close to parallel. Opposite vectors have no single path
+    // between them, so like Quat::from_two_vectors this turns through a fixed perpendicular:
+    // the basis axis self has the least of, with the self part taken out.
+    pub fn slerp(&self, other: &VecN<N>, t: f32) -> VecN<N> {
+        let dot = (self * other).clamp(-1.0, 1.0);
+        let theta = f32::acos(dot);
+        if theta < 1e-4 {
+            return self.lerp(other, t).normalize();
+        }
+        if theta > core::f32::consts::PI - 1e-4 {
+            let mut axis = 0;
+            for i in 1..N {
+                if self[i].abs() < self[axis].abs() {
+                    axis = i;
+                }
+            }
+            let mut perpendicular = self * -self[axis];
+            perpendicular[axis] += 1.0;
+            // Only a single value has no perpendicular, that stays on the line
+            return match perpendicular.try_normalize(1e-6) {
+                Some(perpendicular) => self * f32::cos(t * theta) + perpendicular * f32::sin(t * theta),
+                None => self.lerp(other, t),
+            };
+        }
+        let sin = f32::sin(theta);
+        self * (f32::sin((1.0 - t) * theta) / sin) + other * (f32::sin(t * theta) / sin)
+    }
+
+    // Eases in and out with 3t^2 - 2t^3, t is clamped to [0, 1]
+    pub fn smoothstep(&self, other: &VecN<N>, t: f32) -> VecN<N> {
+        let t = t.clamp(0.0, 1.0);
+        self.lerp(other, t * t * (3.0 - 2.0 * t))
+    }
+
+    // Same as smoothstep but with zero second derivative at the ends, 6t^5 - 15t^4 + 10t^3
+    pub fn smootherstep(&self, other: &VecN<N>, t: f32) -> VecN<N> {
+        let t = t.clamp(0.0, 1.0);
+        self.lerp(other, t * t * t * (t * (6.0 * t - 15.0) + 10.0))
+    }
+
 }
 
 impl VecN<2> {
@@ -53,6 +263,27 @@ impl VecN<2> {
     pub fn angle(theta: f32) -> VecN<2> {
         VecN::from([f32::cos(theta), f32::sin(theta)])
     }
+
+    // Scalar 2D cross product, positive when other is counter clockwise from self
+    pub fn perp_dot(&self, other: &VecN<2>) -> f32 {
+        self[0] * other[1] - self[1] * other[0]
+    }
+
+    // Angle in (-pi, pi], positive when other is counter clockwise from self
+    pub fn signed_angle(&self, other: &VecN<2>) -> f32 {
+        f32::atan2(self.perp_dot(other), self * other)
+    }
+
+    // Treats the vector as a point, use a w of 0 for directions
+    pub fn to_homogeneous(&self) -> VecN<3> {
+        VecN::from([self[0], self[1], 1.0])
+    }
+
+    // Perspective divide, a w of 0 is a direction and is returned as is
+    pub fn from_homogeneous(vec: &VecN<3>) -> VecN<2> {
+        let w = if vec[2] == 0.0 { 1.0 } else { vec[2] };
+        VecN::from([vec[0] / w, vec[1] / w])
+    }
 }
 
 impl VecN<3> {
@@ -62,6 +293,167 @@ impl VecN<3> {
         let z = self[0] * other[1] - self[1] * other[0];
         VecN::from([x,y,z])
     }
+
+    // Angle in (-pi, pi] of the rotation around axis that takes self towards other,
+    // positive when it is counter clockwise looking down the axis
+    pub fn signed_angle(&self, other: &VecN<3>, axis: &VecN<3>) -> f32 {
+        f32::atan2(self.cross(other) * axis.normalize(), self * other)
+    }
+
+    // Tangent and bitangent that make a right handed frame with the normal,
+    // branchless method from Duff et al. "Building an Orthonormal Basis, Revisited"
+    pub fn orthonormal_basis_from(normal: &VecN<3>) -> (VecN<3>, VecN<3>) {
+        let n = normal.normalize();
+        let sign = 1.0f32.copysign(n[2]);
+        let a = -1.0 / (sign + n[2]);
+        let b = n[0] * n[1] * a;
+        let tangent = VecN::from([1.0 + sign * n[0] * n[0] * a, sign * b, -sign * n[0]]);
+        let bitangent = VecN::from([b, sign + n[1] * n[1] * a, -n[1]]);
+        (tangent, bitangent)
+    }
+
+    // Treats the vector as a point, use a w of 0 for directions
+    pub fn to_homogeneous(&self) -> VecN<4> {
+        VecN::from([self[0], self[1], self[2], 1.0])
+    }
+
+    // Perspective divide, a w of 0 is a direction and is returned as is
+    pub fn from_homogeneous(vec: &VecN<4>) -> VecN<3> {
+        let w = if vec[3] == 0.0 { 1.0 } else { vec[3] };
+        VecN::from([vec[0] / w, vec[1] / w, vec[2] / w])
+    }
+}
+
+impl VecN<2> {
+    pub const X: VecN<2> = VecN {vals: [1.0, 0.0]};
+    pub const Y: VecN<2> = VecN {vals: [0.0, 1.0]};
+
+    components! {
+        x, set_x => 0;
+        y, set_y => 1;
+    }
+
+    swizzles! {
+        yx: 2 => [1, 0];
+    }
+
+    pub fn xy0(&self) -> VecN<3> {
+        VecN::from([self[0], self[1], 0.0])
+    }
+
+    pub fn xy1(&self) -> VecN<3> {
+        VecN::from([self[0], self[1], 1.0])
+    }
+}
+
+impl VecN<3> {
+    pub const X: VecN<3> = VecN {vals: [1.0, 0.0, 0.0]};
+    pub const Y: VecN<3> = VecN {vals: [0.0, 1.0, 0.0]};
+    pub const Z: VecN<3> = VecN {vals: [0.0, 0.0, 1.0]};
+
+    components! {
+        x, set_x => 0;
+        y, set_y => 1;
+        z, set_z => 2;
+    }
+
+    swizzles! {
+        xy: 2 => [0, 1];
+        xz: 2 => [0, 2];
+        yx: 2 => [1, 0];
+        yz: 2 => [1, 2];
+        zx: 2 => [2, 0];
+        zy: 2 => [2, 1];
+        xzy: 3 => [0, 2, 1];
+        yxz: 3 => [1, 0, 2];
+        yzx: 3 => [1, 2, 0];
+        zxy: 3 => [2, 0, 1];
+        zyx: 3 => [2, 1, 0];
+    }
+
+    pub fn xyz0(&self) -> VecN<4> {
+        VecN::from([self[0], self[1], self[2], 0.0])
+    }
+
+    pub fn xyz1(&self) -> VecN<4> {
+        VecN::from([self[0], self[1], self[2], 1.0])
+    }
+}
+
+impl VecN<4> {
+    pub const X: VecN<4> = VecN {vals: [1.0, 0.0, 0.0, 0.0]};
+    pub const Y: VecN<4> = VecN {vals: [0.0, 1.0, 0.0, 0.0]};
+    pub const Z: VecN<4> = VecN {vals: [0.0, 0.0, 1.0, 0.0]};
+    pub const W: VecN<4> = VecN {vals: [0.0, 0.0, 0.0, 1.0]};
+
+    components! {
+        x, set_x => 0;
+        y, set_y => 1;
+        z, set_z => 2;
+        w, set_w => 3;
+    }
+
+    swizzles! {
+        xy: 2 => [0, 1];
+        xz: 2 => [0, 2];
+        xw: 2 => [0, 3];
+        yx: 2 => [1, 0];
+        yz: 2 => [1, 2];
+        yw: 2 => [1, 3];
+        zx: 2 => [2, 0];
+        zy: 2 => [2, 1];
+        zw: 2 => [2, 3];
+        wx: 2 => [3, 0];
+        wy: 2 => [3, 1];
+        wz: 2 => [3, 2];
+        xyz: 3 => [0, 1, 2];
+        xyw: 3 => [0, 1, 3];
+        xzy: 3 => [0, 2, 1];
+        xzw: 3 => [0, 2, 3];
+        xwy: 3 => [0, 3, 1];
+        xwz: 3 => [0, 3, 2];
+        yxz: 3 => [1, 0, 2];
+        yxw: 3 => [1, 0, 3];
+        yzx: 3 => [1, 2, 0];
+        yzw: 3 => [1, 2, 3];
+        ywx: 3 => [1, 3, 0];
+        ywz: 3 => [1, 3, 2];
+        zxy: 3 => [2, 0, 1];
+        zxw: 3 => [2, 0, 3];
+        zyx: 3 => [2, 1, 0];
+        zyw: 3 => [2, 1, 3];
+        zwx: 3 => [2, 3, 0];
+        zwy: 3 => [2, 3, 1];
+        wxy: 3 => [3, 0, 1];
+        wxz: 3 => [3, 0, 2];
+        wyx: 3 => [3, 1, 0];
+        wyz: 3 => [3, 1, 2];
+        wzx: 3 => [3, 2, 0];
+        wzy: 3 => [3, 2, 1];
+        xywz: 4 => [0, 1, 3, 2];
+        xzyw: 4 => [0, 2, 1, 3];
+        xzwy: 4 => [0, 2, 3, 1];
+        xwyz: 4 => [0, 3, 1, 2];
+        xwzy: 4 => [0, 3, 2, 1];
+        yxzw: 4 => [1, 0, 2, 3];
+        yxwz: 4 => [1, 0, 3, 2];
+        yzxw: 4 => [1, 2, 0, 3];
+        yzwx: 4 => [1, 2, 3, 0];
+        ywxz: 4 => [1, 3, 0, 2];
+        ywzx: 4 => [1, 3, 2, 0];
+        zxyw: 4 => [2, 0, 1, 3];
+        zxwy: 4 => [2, 0, 3, 1];
+        zyxw: 4 => [2, 1, 0, 3];
+        zywx: 4 => [2, 1, 3, 0];
+        zwxy: 4 => [2, 3, 0, 1];
+        zwyx: 4 => [2, 3, 1, 0];
+        wxyz: 4 => [3, 0, 1, 2];
+        wxzy: 4 => [3, 0, 2, 1];
+        wyxz: 4 => [3, 1, 0, 2];
+        wyzx: 4 => [3, 1, 2, 0];
+        wzxy: 4 => [3, 2, 0, 1];
+        wzyx: 4 => [3, 2, 1, 0];
+    }
 }
 
 impl<const N: usize> Add<&VecN<N>> for &VecN<N> {
@@ -69,9 +461,7 @@ impl<const N: usize> Add<&VecN<N>> for &VecN<N> {
 
     fn add(self,  other: &VecN<N>) -> VecN<N> {
         let mut answer = [0.0; N];
-        for i in 0..N {
-            answer[i] = self[i] + other[i];
-        }
+        simd::add(&self.vals, &other.vals, &mut answer);
         VecN::from(answer)
     }
 }
@@ -103,9 +493,7 @@ impl<const N: usize> Sub<&VecN<N>> for &VecN<N> {
 
     fn sub(self,  other: &VecN<N>) -> VecN<N> {
         let mut answer = [0.0; N];
-        for i in 0..N {
-            answer[i] = self[i] - other[i];
-        }
+        simd::sub(&self.vals, &other.vals, &mut answer);
         VecN::from(answer)
     }
 }
@@ -135,11 +523,7 @@ impl<const N: usize> Mul<&VecN<N>> for &VecN<N> {
     type Output = f32;
 
     fn mul(self, vector: &VecN<N>) -> f32 {
-        let mut answer = 0.0;
-        for i in 0..N {
-            answer += self[i] * vector[i];
-        }
-        answer
+        simd::dot(&self.vals, &vector.vals)
     }
 }
 impl<const N: usize> Mul<VecN<N>> for &VecN<N> {
@@ -168,9 +552,7 @@ impl<const N: usize> Mul<&f32> for &VecN<N> {
 
     fn mul(self,  scalar: &f32) -> VecN<N> {
         let mut answer = [0.0; N];
-        for i in 0..N {
-            answer[i] = self[i] * scalar;
-        }
+        simd::scale(&self.vals, *scalar, &mut answer);
         VecN::from(answer)
     }
 
@@ -301,9 +683,32 @@ impl<const N: usize> IndexMut<usize> for VecN<N> {
     }
 }
 
+// Ranges give back slices of the components, v[1..3] or v[..2]
+macro_rules! range_index {
+    ($($range:ty),*) => {
+        $(
+            impl<const N: usize> Index<$range> for VecN<N> {
+                type Output = [f32];
+
+                fn index(&self, index: $range) -> &[f32] {
+                    &self.vals[index]
+                }
+            }
+
+            impl<const N: usize> IndexMut<$range> for VecN<N> {
+                fn index_mut(&mut self, index: $range) -> &mut [f32] {
+                    &mut self.vals[index]
+                }
+            }
+        )*
+    };
+}
+
+range_index!(Range<usize>, RangeFrom<usize>, RangeTo<usize>, RangeFull, RangeInclusive<usize>, RangeToInclusive<usize>);
+
 impl<const N: usize> From<[f32; N]> for VecN<N> {
     fn from(vals: [f32; N]) -> VecN<N> {
-        VecN{vals}
+        VecN::from_array(vals)
     }
 }
 
@@ -319,6 +724,7 @@ impl From<(f32, f32, f32)> for VecN<3> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<const N: usize> From<&Vec<f32>> for VecN<N> {
     fn from(vals: &Vec<f32>) -> VecN<N> {
         let mut new_vec = [0.0;N];
@@ -329,6 +735,45 @@ impl<const N: usize> From<&Vec<f32>> for VecN<N> {
     }
 }
 
+// Unlike From<&Vec<f32>> the length has to match exactly
+impl<const N: usize> TryFrom<&[f32]> for VecN<N> {
+    type Error = MatrixError;
+
+    fn try_from(vals: &[f32]) -> Result<VecN<N>, MatrixError> {
+        if vals.len() != N {
+            return Err(MatrixError::DimensionMismatch {expected: N, found: vals.len()});
+        }
+        let mut new_vec = [0.0; N];
+        new_vec.copy_from_slice(vals);
+        Ok(VecN::from(new_vec))
+    }
+}
+
+// Parses comma or whitespace separated values, optionally wrapped in brackets like "[1, 2.5, -3]"
+impl<const N: usize> FromStr for VecN<N> {
+    type Err = MatrixError;
+
+    fn from_str(s: &str) -> Result<VecN<N>, MatrixError> {
+        let trimmed = s.trim();
+        let inner = trimmed.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')).unwrap_or(trimmed);
+
+        let mut vals = [0.0; N];
+        let mut found = 0;
+        for (index, part) in inner.split(|c: char| c == ',' || c.is_whitespace()).filter(|part| !part.is_empty()).enumerate() {
+            let val = part.parse::<f32>().map_err(|_| MatrixError::ParseError {index})?;
+            // Extra values are still counted for the error
+            if index < N {
+                vals[index] = val;
+            }
+            found += 1;
+        }
+        if found != N {
+            return Err(MatrixError::DimensionMismatch {expected: N, found});
+        }
+        Ok(VecN::from(vals))
+    }
+}
+
 impl<const N: usize> PartialEq for VecN<N> {
     fn eq(&self, other: &VecN<N>) -> bool {
         for i in 0..N {
@@ -353,6 +798,44 @@ impl<const N: usize> FromIterator<f32> for VecN<N> {
     }
 }
 
+// A value that is known to have a length of one. The only way to make one is through
+// a checked constructor so functions taking a Unit can skip normalizing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit<T> {
+    value: T,
+}
+
+impl<const N: usize> Unit<VecN<N>> {
+    // Normalizes the vector, None if its length is not above eps
+    pub fn try_new(vec: VecN<N>, eps: f32) -> Option<Unit<VecN<N>>> {
+        Some(Unit {value: vec.try_normalize(eps)?})
+    }
+
+    // Only for vectors that are unit length by construction, checked in debug builds
+    pub(crate) fn new_unchecked(vec: VecN<N>) -> Unit<VecN<N>> {
+        debug_assert!((vec.length() - 1.0).abs() < 1e-4, "not a unit vector: {:?}", vec);
+        Unit {value: vec}
+    }
+
+    pub fn into_inner(self) -> VecN<N> {
+        self.value
+    }
+}
+
+impl<T> Deref for Unit<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<const N: usize> From<Unit<VecN<N>>> for VecN<N> {
+    fn from(unit: Unit<VecN<N>>) -> VecN<N> {
+        unit.value
+    }
+}
+
 pub struct VecNIter<const N: usize> {
     vec: VecN<N>,
     at: usize,